@@ -1,109 +1,124 @@
+use advent_of_code::utils::digit_dp::{Automaton, count_and_sum};
+use advent_of_code::utils::digits::{self, repeat};
+use advent_of_code::utils::interval_set::IntervalSet;
 use advent_of_code::utils::{Parsable, parse_input};
 use itertools::Itertools;
 use nom::character::char;
 use nom::multi::separated_list0;
-use nom::sequence::separated_pair;
 use nom::{IResult, Parser};
-use std::ops::Div;
+use std::ops::RangeInclusive;
 
 advent_of_code::solution!(2);
 
-#[derive(Debug, PartialEq)]
-struct Interval {
-    start: u64,
-    end: u64,
+fn parse(input: &str) -> IResult<&str, IntervalSet<u64>> {
+    parse_input(separated_list0(char(','), IntervalSet::parse))
+        .map(|sets| sets.into_iter().flatten().collect())
+        .parse(input)
 }
 
-impl Interval {
-    pub fn new(start: u64, end: u64) -> Interval {
-        Interval { start, end }
-    }
+fn halve(number: u64) -> u64 {
+    split(number, 2)
 }
 
-impl Parsable<'_> for Interval {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (input, (start, end)) =
-            separated_pair(u64::parse, char('-'), u64::parse).parse(input)?;
+fn split(number: u64, parts: u32) -> u64 {
+    let length = digits::count(number);
 
-        Ok((input, Interval::new(start, end)))
+    if !length.is_multiple_of(parts) {
+        return 10u64.pow(length / parts);
     }
-}
 
-fn parse(input: &str) -> IResult<&str, Vec<Interval>> {
-    parse_input(separated_list0(char(','), Interval::parse)).parse(input)
+    let base = digits::prefix(number, length / parts);
+    if repeat(base, parts) >= number {
+        base
+    } else {
+        base + 1
+    }
 }
 
-fn double(number: u64) -> u64 {
-    let length = number.ilog10() + 1;
-    10u64.pow(length) * number + number
-}
+pub fn part_one(input: &str) -> Option<u64> {
+    let (_, data) = parse(input).unwrap();
 
-fn halve(number: u64) -> u64 {
-    let length = number.ilog10() + 1;
-    let first_half = number.div(10u64.pow(length / 2));
+    let result = data
+        .into_iter()
+        .map(|interval| {
+            (halve(*interval.start())..halve(interval.end() + 1))
+                .map(|base| repeat(base, 2))
+                .sum::<u64>() // possible speedup with summing up same-digit numbers
+        })
+        .sum();
 
-    if length % 2 == 1 {
-        10u64.pow(length / 2)
-    } else if double(first_half) >= number {
-        first_half
-    } else {
-        first_half + 1
-    }
+    Some(result)
 }
 
-fn repeat(base: u64, times: u32) -> u64 {
-    let length = base.ilog10() + 1;
-    let mut result = base;
+/// Accepts numbers made of one block of digits repeated `times` times.
+struct RepeatedBlock {
+    times: u32,
+}
 
-    for _ in 0..times - 1 {
-        result = result * 10u64.pow(length) + base;
-    }
+impl Automaton for RepeatedBlock {
+    /// Length of the block, and the block as read so far.
+    type State = (u32, u64);
 
-    result
-}
+    fn start(&self, length: u32) -> Option<Self::State> {
+        length
+            .is_multiple_of(self.times)
+            .then_some((length / self.times, 0))
+    }
 
-fn split(number: u64, parts: u32) -> u64 {
-    let length = number.ilog10() + 1;
-    let part_length = (length - 1) / parts + 1;
-    let base = number.div(10u64.pow(length - part_length));
+    fn step(
+        &self,
+        &(length, block): &Self::State,
+        position: u32,
+        digit: u8,
+    ) -> Option<Self::State> {
+        if position < length {
+            return Some((length, block * 10 + digit as u64));
+        }
+
+        let expected = digits::digits(block).nth((position % length) as usize)?;
+        (expected == digit as u64).then_some((length, block))
+    }
 
-    if length % parts != 0 {
-        10u64.pow(length / parts)
-    } else if repeat(base, parts) >= number {
-        base
-    } else {
-        base + 1
+    fn accept(&self, _state: &Self::State) -> bool {
+        true
     }
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
+pub fn part_one_digit_dp(input: &str) -> Option<u64> {
     let (_, data) = parse(input).unwrap();
+    let automaton = RepeatedBlock { times: 2 };
 
     let result = data
-        .into_iter()
+        .iter()
         .map(|interval| {
-            (halve(interval.start)..halve(interval.end + 1))
-                .map(double)
-                .sum::<u64>() // possible speedup with summing up same-digit numbers
+            count_and_sum(
+                &automaton,
+                *interval.start() as u128,
+                *interval.end() as u128,
+            )
+            .sum
         })
-        .sum();
+        .sum::<u128>();
 
-    Some(result)
+    Some(result as u64)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
     let (_, data) = parse(input).unwrap();
 
-    fn all_invalid_bases_with_parts(interval: &Interval, parts: u32) -> impl Iterator<Item = u64> {
-        (split(interval.start, parts)..split(interval.end + 1, parts))
+    fn all_invalid_bases_with_parts(
+        interval: RangeInclusive<u64>,
+        parts: u32,
+    ) -> impl Iterator<Item = u64> {
+        (split(*interval.start(), parts)..split(interval.end() + 1, parts))
             .map(move |base| repeat(base, parts))
     }
 
     let result = data
         .iter()
         .flat_map(|interval| {
-            let max_parts = interval.end.ilog10() + 1;
-            (2..=max_parts).map(move |parts| (interval, parts))
+            let max_parts = digits::count(*interval.end());
+            (2..=max_parts).map(move |parts| (interval.clone(), parts))
         })
         .flat_map(|(interval, parts)| all_invalid_bases_with_parts(interval, parts))
         .unique()
@@ -125,19 +140,19 @@ mod tests {
             result,
             Ok((
                 "",
-                vec![
-                    Interval::new(11, 22),
-                    Interval::new(95, 115),
-                    Interval::new(998, 1012),
-                    Interval::new(1188511880, 1188511890),
-                    Interval::new(222220, 222224),
-                    Interval::new(1698522, 1698528),
-                    Interval::new(446443, 446449),
-                    Interval::new(38593856, 38593862),
-                    Interval::new(565653, 565659),
-                    Interval::new(824824821, 824824827),
-                    Interval::new(2121212118, 2121212124),
-                ]
+                IntervalSet::from_iter([
+                    11..=22,
+                    95..=115,
+                    998..=1012,
+                    1188511880..=1188511890,
+                    222220..=222224,
+                    1698522..=1698528,
+                    446443..=446449,
+                    38593856..=38593862,
+                    565653..=565659,
+                    824824821..=824824827,
+                    2121212118..=2121212124,
+                ])
             ))
         );
     }
@@ -179,6 +194,12 @@ mod tests {
         assert_eq!(result, Some(1227775554));
     }
 
+    #[test]
+    fn test_part_one_digit_dp() {
+        let result = part_one_digit_dp(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(1227775554));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,15 +1,18 @@
+use advent_of_code::utils::digits;
 use advent_of_code::utils::parse_input_by_lines;
 use nom::IResult;
 use nom::Parser;
 use nom::bytes::complete::take_while1;
+use nom::combinator::map_opt;
 
 advent_of_code::solution!(3);
 
 pub fn parse_line(input: &str) -> IResult<&str, Vec<u64>> {
-    let (input, line) = take_while1(|c: char| c.is_numeric()).parse(input)?;
-    let result: Vec<u64> = line.chars().map(|c| c as u64 - '0' as u64).collect();
-
-    Ok((input, result))
+    map_opt(
+        take_while1(|c: char| c.is_ascii_digit()),
+        digits::parse_digits,
+    )
+    .parse(input)
 }
 
 pub fn parse(input: &str) -> IResult<&str, Vec<Vec<u64>>> {
@@ -18,7 +21,7 @@ pub fn parse(input: &str) -> IResult<&str, Vec<Vec<u64>>> {
 
 fn highest_joltage(input: &[u64]) -> u64 {
     let (l, r) = input
-        .into_iter()
+        .iter()
         .enumerate()
         .fold((0, 0), |(l, r), (index, &x)| match () {
             _ if x > l && index != input.len() - 1 => (x, 0),
@@ -35,7 +38,7 @@ fn highest_joltage_with_digits(input: &[u64], digits: usize) -> u64 {
     for (index, &x) in input.iter().enumerate() {
         let left = input.len() - index;
 
-        while left >= digits - joltage.len() + 1 && *joltage.last().unwrap_or(&999u64) < x {
+        while left > digits - joltage.len() && *joltage.last().unwrap_or(&999u64) < x {
             joltage.pop();
         }
 
@@ -44,7 +47,7 @@ fn highest_joltage_with_digits(input: &[u64], digits: usize) -> u64 {
         }
     }
 
-    joltage.into_iter().fold(0, |acc, x| acc * 10 + x)
+    digits::from_digits(joltage)
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -61,7 +61,7 @@ pub fn part_two(input: &str) -> Option<usize> {
         let neighbours = loc
             .neighbours()
             .into_iter()
-            .filter(|neigh| neighborhoods.get_2d(*neigh).unwrap_or(&None) != &None)
+            .filter(|neigh| neighborhoods.get_2d(*neigh).unwrap_or(&None).is_some())
             .collect::<Vec<_>>();
 
         if neighbours.len() < 4 {
@@ -103,8 +103,8 @@ pub fn part_two(input: &str) -> Option<u64> {
     for col in columns.chain(std::iter::once(vec![' '; input.len()])) {
         if col.iter().all(|c| *c == ' ') {
             result += match op {
-                Operation::Add => operands.iter().fold(0, |acc, x| acc + x),
-                Operation::Multiply => operands.iter().fold(1, |acc, x| acc * x),
+                Operation::Add => operands.iter().sum::<u64>(),
+                Operation::Multiply => operands.iter().product::<u64>(),
             };
 
             operands.clear();
@@ -112,7 +112,7 @@ pub fn part_two(input: &str) -> Option<u64> {
 
         let number = col
             .iter()
-            .filter_map(|c| ('0'..='9').contains(c).then(|| *c as u64 - '0' as u64))
+            .filter_map(|c| c.to_digit(10).map(u64::from))
             .fold(0, |acc, n| acc * 10 + n);
 
         let operation = col.iter().filter_map(|c| Operation::from_char(*c)).next();
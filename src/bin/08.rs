@@ -1,13 +1,11 @@
-use advent_of_code::utils::location::Distance;
 use advent_of_code::utils::location3d::{Location3, location3};
+use advent_of_code::utils::mst::{Kruskal, LazySorted, sorted_lazily};
 use advent_of_code::utils::parse_input_by_lines;
-use advent_of_code::utils::union_find::UnionFind;
 use itertools::Itertools;
 use nom::IResult;
 use nom::Parser;
 use nom::character::complete::char;
 use nom::error::Error;
-use std::cmp::Ordering;
 
 advent_of_code::solution!(8);
 
@@ -15,30 +13,30 @@ fn parse(input: &str) -> IResult<&str, Vec<Location3<u32>>, Error<&str>> {
     parse_input_by_lines(location3(char(','))).parse(input)
 }
 
+fn squared_distance(a: &Location3<u32>, b: &Location3<u32>) -> i64 {
+    let diff = a.map(i64::from) - b.map(i64::from);
+    diff.x * diff.x + diff.y * diff.y + diff.z * diff.z
+}
+
+fn edges(locations: &[Location3<u32>]) -> LazySorted<i64, Location3<u32>> {
+    sorted_lazily(
+        locations
+            .iter()
+            .tuple_combinations()
+            .map(|(x, y)| (squared_distance(x, y), *x, *y)),
+    )
+}
+
 pub fn part_one_connections(input: &str, connections: usize) -> Option<usize> {
     let (_, locations) = parse(input).unwrap();
 
-    let mut cluster_data: UnionFind<_> = locations.iter().cloned().collect();
-    let edges = locations
-        .iter()
-        .tuple_combinations()
-        .map(|(x, y)| (x.distance::<f64>(y), (x, y)))
-        .sorted_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let mut kruskal = Kruskal::from_sorted(
+        locations.iter().cloned(),
+        edges(&locations).take(connections),
+    );
+    for _ in kruskal.by_ref() {}
 
-    for (_, (left, right)) in edges.take(connections) {
-        cluster_data.union(left, right);
-    }
-
-    locations
-        .iter()
-        .filter_map(|location| cluster_data.find(location))
-        .counts()
-        .into_iter()
-        .map(|(_, count)| count)
-        .sorted()
-        .rev()
-        .take(3)
-        .product1()
+    kruskal.component_sizes().into_iter().take(3).product1()
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
@@ -48,28 +46,11 @@ pub fn part_one(input: &str) -> Option<usize> {
 pub fn part_two(input: &str) -> Option<u32> {
     let (_, locations) = parse(input).unwrap();
 
-    let mut cluster_data: UnionFind<_> = locations.iter().cloned().collect();
-    let edges = locations
-        .iter()
-        .tuple_combinations()
-        .map(|(x, y)| (x.distance::<f64>(y), (x, y)))
-        .sorted_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
-
-    let mut circuits = locations.len();
-
-    for (_, (left, right)) in edges {
-        if let Some((x, y)) = cluster_data.union(left, right) {
-            if x != y {
-                circuits -= 1;
-            }
-
-            if circuits == 1 {
-                return Some(left.x * right.x);
-            }
-        }
-    }
+    let mut kruskal = Kruskal::from_sorted(locations.iter().cloned(), edges(&locations));
 
-    None
+    kruskal
+        .connecting_edge()
+        .map(|connection| connection.left.x * connection.right.x)
 }
 
 #[cfg(test)]
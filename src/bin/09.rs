@@ -28,7 +28,7 @@ pub fn part_one(input: &str) -> Option<i64> {
         .map(|(a, b)| area(a, b))
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(_input: &str) -> Option<u64> {
     None
 }
 
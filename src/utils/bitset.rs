@@ -0,0 +1,417 @@
+use num::{PrimInt, Unsigned};
+use std::hash::{Hash, Hasher};
+use std::ops::{BitAnd, BitOr, BitXor, Not, Sub};
+
+/// Iterates the set bits of a single word, lowest first, offset by `base`.
+#[derive(Debug, Clone)]
+pub struct Ones<T> {
+    word: T,
+    base: usize,
+}
+
+impl<T: PrimInt + Unsigned> Iterator for Ones<T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.word.is_zero() {
+            return None;
+        }
+
+        let bit = self.word.trailing_zeros() as usize;
+        self.word = self.word & (self.word - T::one());
+        Some(self.base + bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.word.count_ones() as usize;
+        (count, Some(count))
+    }
+}
+
+fn ones<T: PrimInt + Unsigned>(word: T, base: usize) -> Ones<T> {
+    Ones { word, base }
+}
+
+fn split(bit: usize) -> (usize, u64) {
+    (bit / 64, 1 << (bit % 64))
+}
+
+/// Growable set of small non-negative integers, e.g. dense ids or grid cell indices.
+#[derive(Debug, Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Preallocates room for `0..bits`; larger values still work.
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    /// Returns whether `bit` was newly inserted.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        let fresh = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        fresh
+    }
+
+    /// Returns whether `bit` was present.
+    pub fn remove(&mut self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        let Some(word) = self.words.get_mut(word) else {
+            return false;
+        };
+
+        let present = *word & mask != 0;
+        *word &= !mask;
+        present
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        self.words.get(word).is_some_and(|word| word & mask != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &word)| ones(word, index * 64))
+    }
+
+    fn zip_with(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (index, word) in self.words.iter_mut().enumerate() {
+            *word = f(*word, other.words.get(index).copied().unwrap_or(0));
+        }
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a | b);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a & b);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a & !b);
+    }
+
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a ^ b);
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(index, word)| word & !other.words.get(index).copied().unwrap_or(0) == 0)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    /// The words without trailing zeros, so equal sets compare and hash equally.
+    fn significant(&self) -> &[u64] {
+        let len = self
+            .words
+            .iter()
+            .rposition(|&word| word != 0)
+            .map_or(0, |last| last + 1);
+        &self.words[..len]
+    }
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant() == other.significant()
+    }
+}
+
+impl Eq for BitSet {}
+
+impl Hash for BitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant().hash(state);
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for bit in iter {
+            self.insert(bit);
+        }
+    }
+}
+
+/// Bit set of `64 * WORDS` bits that lives on the stack and is `Copy`, so it can be part of a
+/// search state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FixedBitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> Default for FixedBitSet<WORDS> {
+    fn default() -> Self {
+        Self { words: [0; WORDS] }
+    }
+}
+
+impl<const WORDS: usize> FixedBitSet<WORDS> {
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether `bit` was newly inserted; panics if `bit` is out of capacity.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        let fresh = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        fresh
+    }
+
+    /// Returns whether `bit` was present.
+    pub fn remove(&mut self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        let Some(word) = self.words.get_mut(word) else {
+            return false;
+        };
+
+        let present = *word & mask != 0;
+        *word &= !mask;
+        present
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        let (word, mask) = split(bit);
+        self.words.get(word).is_some_and(|word| word & mask != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &word)| ones(word, index * 64))
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        Self {
+            words: std::array::from_fn(|index| f(self.words[index], other.words[index])),
+        }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.zip_with(*other, |a, b| a & !b).is_empty()
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        (*self & *other).is_empty()
+    }
+}
+
+impl<const WORDS: usize> BitOr for FixedBitSet<WORDS> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+}
+
+impl<const WORDS: usize> BitAnd for FixedBitSet<WORDS> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+}
+
+impl<const WORDS: usize> BitXor for FixedBitSet<WORDS> {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+}
+
+impl<const WORDS: usize> Sub for FixedBitSet<WORDS> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+}
+
+impl<const WORDS: usize> Not for FixedBitSet<WORDS> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self {
+            words: self.words.map(|word| !word),
+        }
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for FixedBitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for bit in iter {
+            set.insert(bit);
+        }
+        set
+    }
+}
+
+/// Set of values below 64 (or the width of another unsigned `T`) packed into one integer, for
+/// compressing things like "which valves are open" into a cheap `HashMap` key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SmallSet<T = u64>(pub T);
+
+impl<T: PrimInt + Unsigned> SmallSet<T> {
+    pub const CAPACITY: usize = size_of::<T>() * 8;
+
+    pub fn new() -> Self {
+        Self(T::zero())
+    }
+
+    /// All values in `0..n`.
+    pub fn full(n: usize) -> Self {
+        if n >= Self::CAPACITY {
+            Self(!T::zero())
+        } else {
+            Self((T::one() << n) - T::one())
+        }
+    }
+
+    pub fn bits(&self) -> T {
+        self.0
+    }
+
+    fn mask(value: usize) -> T {
+        assert!(value < Self::CAPACITY, "{value} does not fit a SmallSet");
+        T::one() << value
+    }
+
+    /// Returns whether `value` was newly inserted.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let fresh = !self.contains(value);
+        self.0 = self.0 | Self::mask(value);
+        fresh
+    }
+
+    /// Returns whether `value` was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let present = self.contains(value);
+        self.0 = self.0 & !Self::mask(value);
+        present
+    }
+
+    pub fn with(self, value: usize) -> Self {
+        Self(self.0 | Self::mask(value))
+    }
+
+    pub fn without(self, value: usize) -> Self {
+        Self(self.0 & !Self::mask(value))
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < Self::CAPACITY && !(self.0 & (T::one() << value)).is_zero()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn iter(&self) -> Ones<T> {
+        ones(self.0, 0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        (self.0 & !other.0).is_zero()
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        (self.0 & other.0).is_zero()
+    }
+}
+
+impl<T: PrimInt + Unsigned> IntoIterator for SmallSet<T> {
+    type Item = usize;
+    type IntoIter = Ones<T>;
+
+    fn into_iter(self) -> Ones<T> {
+        self.iter()
+    }
+}
+
+impl<T: PrimInt + Unsigned> FromIterator<usize> for SmallSet<T> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::with)
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The sequence `x0, f(x0), f(f(x0)), ...` repeats from step `start` with period `length`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step holding the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// Replays the sequence to find the state at step `n`, taking at most `start + length` steps.
+    pub fn state_at<S>(&self, initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+        (0..self.reduce(n)).fold(initial, |state, _| step(&state))
+    }
+}
+
+/// Floyd's tortoise and hare; needs only `Eq`, and keeps just two states around.
+pub fn floyd<S: Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm; like [`floyd`], but usually with fewer calls to `step`.
+pub fn brent<S: Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = (0..length).fold(initial, |state, _| step(&state));
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Records states until one repeats, stopping early if step `limit` is reached first.
+fn record<S: Hash + Eq + Clone>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    limit: Option<usize>,
+) -> (Option<Cycle>, Vec<S>) {
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let length = states.len() - start;
+            return (Some(Cycle { start, length }), states);
+        }
+        if limit == Some(states.len()) {
+            states.push(state);
+            return (None, states);
+        }
+
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Finds the cycle by remembering every state; returns it with the states of steps
+/// `0..start + length`.
+pub fn detect<S: Hash + Eq + Clone>(initial: S, step: impl FnMut(&S) -> S) -> (Cycle, Vec<S>) {
+    let (cycle, states) = record(initial, step, None);
+    (
+        cycle.expect("recording without a limit only stops on a repeat"),
+        states,
+    )
+}
+
+/// The state after `n` steps, skipping ahead as soon as a cycle is found.
+pub fn state_at<S: Hash + Eq + Clone>(initial: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    state_at_with_values(initial, step, n, |_| ()).0
+}
+
+/// Like [`state_at`], also returning `value` of every state in one period of the cycle, starting
+/// at `cycle.start`; the values are empty if step `n` came before any repeat.
+pub fn state_at_with_values<S, V>(
+    initial: S,
+    step: impl FnMut(&S) -> S,
+    n: usize,
+    value: impl Fn(&S) -> V,
+) -> (S, Vec<V>)
+where
+    S: Hash + Eq + Clone,
+{
+    let (cycle, mut states) = record(initial, step, Some(n));
+
+    match cycle {
+        Some(cycle) => {
+            let values = states[cycle.start..].iter().map(value).collect();
+            (states.swap_remove(cycle.reduce(n)), values)
+        }
+        None => (states.pop().expect("step n was recorded"), vec![]),
+    }
+}
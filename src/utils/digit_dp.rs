@@ -0,0 +1,229 @@
+use crate::utils::digits;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// State machine reading the decimal digits of a number, most significant first.
+pub trait Automaton {
+    type State: Clone + Eq + Hash;
+
+    /// The state before the first digit of a number with exactly `length` digits, or `None` to
+    /// reject every number of that length.
+    fn start(&self, length: u32) -> Option<Self::State>;
+
+    /// Reads `digit` at `position` (zero-based from the left), or `None` to reject.
+    fn step(&self, state: &Self::State, position: u32, digit: u8) -> Option<Self::State>;
+
+    fn accept(&self, state: &Self::State) -> bool;
+}
+
+/// How many numbers were accepted, and their sum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub count: u128,
+    pub sum: u128,
+}
+
+struct Search<'a, A: Automaton> {
+    automaton: &'a A,
+    length: u32,
+    low: Vec<u8>,
+    high: Vec<u8>,
+    memo: HashMap<(u32, A::State), Tally>,
+}
+
+impl<A: Automaton> Search<'_, A> {
+    /// Tally of the digits from `position` on, where `tight_low` / `tight_high` mean the digits so
+    /// far equal the prefix of the respective bound.
+    fn tally(
+        &mut self,
+        position: u32,
+        state: A::State,
+        tight_low: bool,
+        tight_high: bool,
+    ) -> Tally {
+        if position == self.length {
+            let accepted = self.automaton.accept(&state) as u128;
+            return Tally {
+                count: accepted,
+                sum: 0,
+            };
+        }
+
+        let free = !tight_low && !tight_high;
+        if free && let Some(&tally) = self.memo.get(&(position, state.clone())) {
+            return tally;
+        }
+
+        let index = position as usize;
+        let from = if tight_low { self.low[index] } else { 0 };
+        let to = if tight_high { self.high[index] } else { 9 };
+        let place = 10u128.pow(self.length - position - 1);
+
+        let mut total = Tally::default();
+        for digit in from..=to {
+            let Some(next) = self.automaton.step(&state, position, digit) else {
+                continue;
+            };
+
+            let rest = self.tally(
+                position + 1,
+                next,
+                tight_low && digit == from,
+                tight_high && digit == to,
+            );
+            total.count += rest.count;
+            total.sum += digit as u128 * place * rest.count + rest.sum;
+        }
+
+        if free {
+            self.memo.insert((position, state), total);
+        }
+        total
+    }
+}
+
+fn digits_of(n: u128) -> Vec<u8> {
+    digits::digits(n).map(|digit| digit as u8).collect()
+}
+
+/// Counts and sums every `n` in `low..=high` whose decimal digits the automaton accepts.
+pub fn count_and_sum<A: Automaton>(automaton: &A, low: u128, high: u128) -> Tally {
+    let mut total = Tally::default();
+    if low > high {
+        return total;
+    }
+
+    for length in digits::count(low)..=digits::count(high) {
+        let Some(start) = automaton.start(length) else {
+            continue;
+        };
+
+        let smallest = if length == 1 {
+            0
+        } else {
+            10u128.pow(length - 1)
+        };
+        let largest = 10u128
+            .checked_pow(length)
+            .map_or(u128::MAX, |power| power - 1);
+
+        let mut search = Search {
+            automaton,
+            length,
+            low: digits_of(low.max(smallest)),
+            high: digits_of(high.min(largest)),
+            memo: HashMap::new(),
+        };
+
+        let tally = search.tally(0, start, true, true);
+        total.count += tally.count;
+        total.sum += tally.sum;
+    }
+
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Digit sum divisible by `modulus`.
+    struct DigitSum {
+        modulus: u32,
+    }
+
+    impl Automaton for DigitSum {
+        type State = u32;
+
+        fn start(&self, _length: u32) -> Option<u32> {
+            Some(0)
+        }
+
+        fn step(&self, &sum: &u32, _position: u32, digit: u8) -> Option<u32> {
+            Some((sum + digit as u32) % self.modulus)
+        }
+
+        fn accept(&self, &sum: &u32) -> bool {
+            sum == 0
+        }
+    }
+
+    /// Even length, without any zero digit.
+    struct EvenWithoutZeros;
+
+    impl Automaton for EvenWithoutZeros {
+        type State = ();
+
+        fn start(&self, length: u32) -> Option<()> {
+            length.is_multiple_of(2).then_some(())
+        }
+
+        fn step(&self, _state: &(), _position: u32, digit: u8) -> Option<()> {
+            (digit != 0).then_some(())
+        }
+
+        fn accept(&self, _state: &()) -> bool {
+            true
+        }
+    }
+
+    fn brute(low: u128, high: u128, accept: impl Fn(u128) -> bool) -> Tally {
+        (low..=high)
+            .filter(|&n| accept(n))
+            .fold(Tally::default(), |tally, n| Tally {
+                count: tally.count + 1,
+                sum: tally.sum + n,
+            })
+    }
+
+    #[test]
+    fn test_tight_bounds() {
+        let automaton = DigitSum { modulus: 7 };
+        let digit_sum = |n: u128| digits::digits(n).sum::<u128>();
+
+        for (low, high) in [
+            (0, 0),
+            (0, 9),
+            (7, 1234),
+            (95, 105),
+            (999, 1001),
+            (123, 123),
+            (4070, 4079),
+            (5, 3),
+        ] {
+            assert_eq!(
+                count_and_sum(&automaton, low, high),
+                brute(low, high, |n| digit_sum(n) % 7 == 0),
+                "{low}..={high}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejections() {
+        let even_without_zeros = |n: u128| {
+            digits::count(n).is_multiple_of(2) && digits::digits(n).all(|digit| digit != 0)
+        };
+
+        for (low, high) in [(0, 200), (5, 99), (10, 11), (1000, 1300), (87, 8765)] {
+            assert_eq!(
+                count_and_sum(&EvenWithoutZeros, low, high),
+                brute(low, high, even_without_zeros),
+                "{low}..={high}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_bounds() {
+        let multiples = 10u128.pow(18) / 9;
+
+        assert_eq!(
+            count_and_sum(&DigitSum { modulus: 9 }, 0, 10u128.pow(18)),
+            Tally {
+                count: multiples + 1,
+                sum: 9 * multiples * (multiples + 1) / 2,
+            }
+        );
+    }
+}
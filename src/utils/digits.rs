@@ -0,0 +1,153 @@
+use num::PrimInt;
+
+fn radix<T: PrimInt>(base: u32) -> T {
+    debug_assert!(base >= 2, "base must be at least 2");
+    T::from(base).expect("base fits the integer type")
+}
+
+/// Number of digits of a non-negative `n`; zero has one digit.
+///
+/// Like every function here, works in base 10, with an `_in` variant taking any base from 2 up.
+pub fn count<T: PrimInt>(n: T) -> u32 {
+    count_in(n, 10)
+}
+
+pub fn count_in<T: PrimInt>(mut n: T, base: u32) -> u32 {
+    let base = radix(base);
+    let mut count = 1;
+    while n >= base {
+        n = n / base;
+        count += 1;
+    }
+    count
+}
+
+/// Digits of `n`, most significant first; `.rev()` gives least significant first.
+#[derive(Debug, Clone)]
+pub struct Digits<T> {
+    n: T,
+    base: T,
+    low: u32,
+    high: u32,
+}
+
+impl<T: PrimInt> Digits<T> {
+    fn at(&self, position: u32) -> T {
+        (self.n / self.base.pow(position)) % self.base
+    }
+}
+
+impl<T: PrimInt> Iterator for Digits<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        (self.low < self.high).then(|| {
+            self.high -= 1;
+            self.at(self.high)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.high - self.low) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<T: PrimInt> DoubleEndedIterator for Digits<T> {
+    fn next_back(&mut self) -> Option<T> {
+        (self.low < self.high).then(|| {
+            self.low += 1;
+            self.at(self.low - 1)
+        })
+    }
+}
+
+impl<T: PrimInt> ExactSizeIterator for Digits<T> {}
+
+pub fn digits<T: PrimInt>(n: T) -> Digits<T> {
+    digits_in(n, 10)
+}
+
+pub fn digits_in<T: PrimInt>(n: T, base: u32) -> Digits<T> {
+    Digits {
+        n,
+        base: radix(base),
+        low: 0,
+        high: count_in(n, base),
+    }
+}
+
+/// Builds a number from digits, most significant first.
+pub fn from_digits<T: PrimInt>(digits: impl IntoIterator<Item = T>) -> T {
+    from_digits_in(digits, 10)
+}
+
+pub fn from_digits_in<T: PrimInt>(digits: impl IntoIterator<Item = T>, base: u32) -> T {
+    let base = radix(base);
+    digits
+        .into_iter()
+        .fold(T::zero(), |number, digit| number * base + digit)
+}
+
+/// Parses a string of decimal digits into its digits, e.g. `"907"` into `[9, 0, 7]`.
+pub fn parse_digits<T: PrimInt>(input: &str) -> Option<Vec<T>> {
+    input
+        .chars()
+        .map(|c| c.to_digit(10).and_then(T::from))
+        .collect()
+}
+
+/// `concat(12, 345) == 12345`.
+pub fn concat<T: PrimInt>(left: T, right: T) -> T {
+    concat_in(left, right, 10)
+}
+
+pub fn concat_in<T: PrimInt>(left: T, right: T, base: u32) -> T {
+    left * radix::<T>(base).pow(count_in(right, base)) + right
+}
+
+/// `repeat(12, 3) == 121212`; zero times gives zero.
+pub fn repeat<T: PrimInt>(n: T, times: u32) -> T {
+    repeat_in(n, times, 10)
+}
+
+pub fn repeat_in<T: PrimInt>(n: T, times: u32, base: u32) -> T {
+    let shift = radix::<T>(base).pow(count_in(n, base));
+    (0..times).fold(T::zero(), |number, _| number * shift + n)
+}
+
+/// The first `k` digits, or all of `n` if it has at most `k`.
+pub fn prefix<T: PrimInt>(n: T, k: u32) -> T {
+    prefix_in(n, k, 10)
+}
+
+pub fn prefix_in<T: PrimInt>(n: T, k: u32, base: u32) -> T {
+    let count = count_in(n, base);
+    if k >= count {
+        n
+    } else {
+        n / radix::<T>(base).pow(count - k)
+    }
+}
+
+/// The last `k` digits, or all of `n` if it has at most `k`.
+pub fn suffix<T: PrimInt>(n: T, k: u32) -> T {
+    suffix_in(n, k, 10)
+}
+
+pub fn suffix_in<T: PrimInt>(n: T, k: u32, base: u32) -> T {
+    if k >= count_in(n, base) {
+        n
+    } else {
+        n % radix::<T>(base).pow(k)
+    }
+}
+
+/// `reverse(1230) == 321`.
+pub fn reverse<T: PrimInt>(n: T) -> T {
+    reverse_in(n, 10)
+}
+
+pub fn reverse_in<T: PrimInt>(n: T, base: u32) -> T {
+    from_digits_in(digits_in(n, base).rev(), base)
+}
@@ -0,0 +1,120 @@
+use crate::utils::graph::parse::Direction;
+use crate::utils::graph::{Graph, Neighbours};
+use std::collections::HashSet;
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+use std::path::Path;
+
+fn quote(node: &impl Display) -> String {
+    format!(
+        "\"{}\"",
+        node.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+fn header(direction: Direction) -> (&'static str, &'static str) {
+    match direction {
+        Direction::Directed => ("digraph", "->"),
+        Direction::Undirected => ("graph", "--"),
+    }
+}
+
+/// Renders the edges leaving `nodes` as Graphviz DOT, optionally labelled.
+///
+/// For undirected graphs each edge is written once, even if both directions are present.
+fn render<N, L>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl Fn(&N) -> Vec<(N, Option<L>)>,
+    direction: Direction,
+) -> String
+where
+    N: Eq + Hash + Clone + Display,
+    L: Display,
+{
+    let (kind, arrow) = header(direction);
+    let mut seen = HashSet::new();
+    let mut dot = format!("{kind} {{\n");
+
+    for node in nodes {
+        let _ = writeln!(dot, "    {};", quote(&node));
+
+        for (next, label) in edges(&node) {
+            if direction == Direction::Undirected && seen.contains(&(next.clone(), node.clone())) {
+                continue;
+            }
+            seen.insert((node.clone(), next.clone()));
+
+            let _ = match label {
+                Some(label) => writeln!(
+                    dot,
+                    "    {} {arrow} {} [label={}];",
+                    quote(&node),
+                    quote(&next),
+                    quote(&label)
+                ),
+                None => writeln!(dot, "    {} {arrow} {};", quote(&node), quote(&next)),
+            };
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn to_dot<N, G>(nodes: impl IntoIterator<Item = N>, graph: &G, direction: Direction) -> String
+where
+    N: Eq + Hash + Clone + Display,
+    G: Neighbours<N>,
+{
+    let edges = |node: &N| {
+        graph
+            .neighbours(node)
+            .into_iter()
+            .map(|next| (next, None::<&str>))
+            .collect()
+    };
+
+    render(nodes, edges, direction)
+}
+
+/// Like [`to_dot`], with every edge labelled by its weight.
+pub fn weighted_to_dot<N, G>(
+    nodes: impl IntoIterator<Item = N>,
+    graph: &G,
+    direction: Direction,
+) -> String
+where
+    N: Eq + Hash + Clone + Display,
+    G: Graph<N>,
+    G::Weight: Display,
+{
+    let edges = |node: &N| {
+        graph
+            .edges(node)
+            .into_iter()
+            .map(|(next, weight)| (next, Some(weight)))
+            .collect()
+    };
+
+    render(nodes, edges, direction)
+}
+
+/// Renders a partition, e.g. `UnionFind::groups`, with every group in its own cluster.
+pub fn partition_to_dot<N: Display>(groups: impl IntoIterator<Item = Vec<N>>) -> String {
+    let mut dot = String::from("graph {\n");
+
+    for (index, group) in groups.into_iter().enumerate() {
+        let _ = writeln!(dot, "    subgraph cluster_{index} {{");
+        for node in group {
+            let _ = writeln!(dot, "        {};", quote(&node));
+        }
+        dot.push_str("    }\n");
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn write_dot(path: impl AsRef<Path>, dot: &str) -> std::io::Result<()> {
+    std::fs::write(path, dot)
+}
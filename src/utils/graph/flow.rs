@@ -0,0 +1,341 @@
+use crate::utils::interner::Interner;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+struct Edge {
+    to: usize,
+    capacity: u64,
+    residual: u64,
+}
+
+/// A minimum cut: its total capacity, the two sides, and the edges crossing from source to sink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut<N> {
+    pub value: u64,
+    pub source_side: Vec<N>,
+    pub sink_side: Vec<N>,
+    pub edges: Vec<(N, N)>,
+}
+
+impl<N> Cut<N> {
+    fn map<U>(self, f: impl Fn(N) -> U) -> Cut<U> {
+        Cut {
+            value: self.value,
+            source_side: self.source_side.into_iter().map(&f).collect(),
+            sink_side: self.sink_side.into_iter().map(&f).collect(),
+            edges: self.edges.into_iter().map(|(a, b)| (f(a), f(b))).collect(),
+        }
+    }
+}
+
+/// Flow network over dense node ids, solved with Dinic's algorithm.
+///
+/// Edges are stored in pairs, so edge `e` and `e ^ 1` are each other's reverse.
+#[derive(Debug, Clone, Default)]
+pub struct FlowNetwork {
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            edges: vec![],
+            adjacency: vec![vec![]; nodes],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    fn add_pair(&mut self, from: usize, to: usize, forward: u64, backward: u64) {
+        let needed = from.max(to) + 1;
+        if self.adjacency.len() < needed {
+            self.adjacency.resize(needed, vec![]);
+        }
+
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(Edge {
+            to,
+            capacity: forward,
+            residual: forward,
+        });
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(Edge {
+            to: from,
+            capacity: backward,
+            residual: backward,
+        });
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: u64) {
+        self.add_pair(from, to, capacity, 0);
+    }
+
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, capacity: u64) {
+        self.add_pair(a, b, capacity, capacity);
+    }
+
+    fn reset(&mut self) {
+        for edge in &mut self.edges {
+            edge.residual = edge.capacity;
+        }
+    }
+
+    fn levels(&self, source: usize) -> Vec<Option<usize>> {
+        let mut levels = vec![None; self.len()];
+        levels[source] = Some(0);
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            let next_level = levels[node].map(|level| level + 1);
+
+            for &e in &self.adjacency[node] {
+                let edge = &self.edges[e];
+                if edge.residual > 0 && levels[edge.to].is_none() {
+                    levels[edge.to] = next_level;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        levels
+    }
+
+    /// Pushes one augmenting path through the level graph, returning the amount pushed.
+    fn augment(
+        &mut self,
+        source: usize,
+        sink: usize,
+        levels: &mut [Option<usize>],
+        next: &mut [usize],
+    ) -> u64 {
+        let mut path: Vec<usize> = vec![];
+        let mut node = source;
+
+        loop {
+            if node == sink {
+                let pushed = path
+                    .iter()
+                    .map(|&e| self.edges[e].residual)
+                    .min()
+                    .unwrap_or(0);
+                for &e in &path {
+                    self.edges[e].residual -= pushed;
+                    self.edges[e ^ 1].residual += pushed;
+                }
+                return pushed;
+            }
+
+            let advance = self.adjacency[node][next[node]..].iter().position(|&e| {
+                let edge = &self.edges[e];
+                edge.residual > 0
+                    && levels[edge.to].is_some()
+                    && levels[edge.to] == levels[node].map(|level| level + 1)
+            });
+
+            match advance {
+                Some(offset) => {
+                    next[node] += offset;
+                    let e = self.adjacency[node][next[node]];
+                    path.push(e);
+                    node = self.edges[e].to;
+                }
+                None => {
+                    // Dead end: never come back here in this phase, and retreat one step.
+                    next[node] = self.adjacency[node].len();
+                    levels[node] = None;
+
+                    let Some(e) = path.pop() else {
+                        return 0;
+                    };
+                    node = self.edges[e ^ 1].to;
+                    next[node] += 1;
+                }
+            }
+        }
+    }
+
+    /// Maximum flow from `source` to `sink`, recomputed from scratch on every call.
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> u64 {
+        self.reset();
+        if source == sink {
+            return 0;
+        }
+
+        let mut total = 0;
+        loop {
+            let mut levels = self.levels(source);
+            if levels[sink].is_none() {
+                return total;
+            }
+
+            let mut next = vec![0; self.len()];
+            loop {
+                let pushed = self.augment(source, sink, &mut levels, &mut next);
+                if pushed == 0 {
+                    break;
+                }
+                total += pushed;
+            }
+        }
+    }
+
+    pub fn min_cut(&mut self, source: usize, sink: usize) -> Cut<usize> {
+        let value = self.max_flow(source, sink);
+
+        let reachable = self.levels(source);
+        let (source_side, sink_side) = (0..self.len()).partition(|&node| reachable[node].is_some());
+
+        let edges = self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| edge.capacity > 0)
+            .map(|(e, edge)| (self.edges[e ^ 1].to, edge.to))
+            .filter(|&(from, to)| reachable[from].is_some() && reachable[to].is_none())
+            .collect();
+
+        Cut {
+            value,
+            source_side,
+            sink_side,
+            edges,
+        }
+    }
+}
+
+fn unit_network<N: Eq + Hash + Clone>(
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> (Interner<N>, FlowNetwork) {
+    let mut interner = Interner::new();
+    let mut network = FlowNetwork::default();
+
+    for (a, b) in edges {
+        let (a, b) = (interner.intern(a), interner.intern(b));
+        network.add_undirected_edge(a, b, 1);
+    }
+
+    (interner, network)
+}
+
+/// Minimum cut between `source` and `sink` of an undirected graph where every edge counts once.
+pub fn unit_min_cut<N: Eq + Hash + Clone>(
+    edges: impl IntoIterator<Item = (N, N)>,
+    source: &N,
+    sink: &N,
+) -> Option<Cut<N>> {
+    let (interner, mut network) = unit_network(edges);
+    let (source, sink) = (interner.get(source)?, interner.get(sink)?);
+
+    Some(network.min_cut(source, sink).map(|id| interner[id].clone()))
+}
+
+/// Smallest cut splitting an undirected graph into two parts, every edge counting once.
+///
+/// Tries every node as the sink against a fixed source, stopping early once a cut of at most
+/// `enough` edges is found (e.g. `3` for "cut three wires"; `0` to always search exhaustively).
+pub fn unit_global_min_cut<N: Eq + Hash + Clone>(
+    edges: impl IntoIterator<Item = (N, N)>,
+    enough: u64,
+) -> Option<Cut<N>> {
+    let (interner, mut network) = unit_network(edges);
+    let mut best: Option<Cut<usize>> = None;
+
+    for sink in 1..network.len() {
+        let cut = network.min_cut(0, sink);
+        if best.as_ref().is_none_or(|best| cut.value < best.value) {
+            let done = cut.value <= enough;
+            best = Some(cut);
+            if done {
+                break;
+            }
+        }
+    }
+
+    best.map(|cut| cut.map(|id| interner[id].clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        let mut network = FlowNetwork::new(6);
+        for (from, to, capacity) in [
+            (0, 1, 16),
+            (0, 2, 13),
+            (1, 3, 12),
+            (2, 1, 4),
+            (2, 4, 14),
+            (3, 2, 9),
+            (3, 5, 20),
+            (4, 3, 7),
+            (4, 5, 4),
+        ] {
+            network.add_edge(from, to, capacity);
+        }
+
+        assert_eq!(network.max_flow(0, 5), 23);
+        assert_eq!(network.max_flow(0, 5), 23);
+
+        let cut = network.min_cut(0, 5);
+        assert_eq!(cut.value, 23);
+        assert_eq!(cut.source_side, vec![0, 1, 2, 4]);
+        assert_eq!(cut.sink_side, vec![3, 5]);
+        assert_eq!(cut.edges, vec![(1, 3), (4, 3), (4, 5)]);
+    }
+
+    #[test]
+    fn test_retreat_from_dead_end() {
+        let mut network = FlowNetwork::new(4);
+        network.add_edge(0, 1, 5);
+        network.add_edge(1, 2, 5);
+        network.add_edge(1, 3, 5);
+
+        assert_eq!(network.max_flow(0, 3), 5);
+    }
+
+    #[test]
+    fn test_flow_is_rerouted() {
+        let mut network = FlowNetwork::new(4);
+        for (from, to) in [(0, 1), (0, 2), (1, 2), (1, 3), (2, 3)] {
+            network.add_edge(from, to, 1);
+        }
+
+        assert_eq!(network.max_flow(0, 3), 2);
+        assert_eq!(network.max_flow(3, 0), 0);
+    }
+
+    #[test]
+    fn test_unit_cuts() {
+        let clique = |nodes: [char; 4]| {
+            (0..4).flat_map(move |i| (i + 1..4).map(move |j| (nodes[i], nodes[j])))
+        };
+        let edges = clique(['a', 'b', 'c', 'd'])
+            .chain(clique(['e', 'f', 'g', 'h']))
+            .chain([('a', 'e'), ('b', 'f')])
+            .collect::<Vec<_>>();
+
+        let cut = unit_global_min_cut(edges.clone(), 0).unwrap();
+        assert_eq!(cut.value, 2);
+        let mut sides = [cut.source_side, cut.sink_side].map(|mut side| {
+            side.sort();
+            side
+        });
+        sides.sort();
+        assert_eq!(sides, [vec!['a', 'b', 'c', 'd'], vec!['e', 'f', 'g', 'h']]);
+
+        let cut = unit_min_cut(edges.clone(), &'c', &'g').unwrap();
+        assert_eq!(cut.value, 2);
+        assert_eq!(cut.edges, vec![('a', 'e'), ('b', 'f')]);
+        assert_eq!(unit_min_cut(edges, &'c', &'z'), None);
+    }
+}
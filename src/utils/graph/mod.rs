@@ -0,0 +1,118 @@
+pub mod dot;
+pub mod flow;
+pub mod parse;
+pub mod paths;
+pub mod search;
+pub mod structure;
+
+use crate::utils::location::{Access2d, Location};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+/// Unweighted adjacency: the nodes reachable in one step from `node`.
+pub trait Neighbours<N> {
+    fn neighbours(&self, node: &N) -> impl IntoIterator<Item = N>;
+}
+
+/// Weighted adjacency: the nodes reachable in one step from `node`, with the cost of that step.
+pub trait Graph<N> {
+    type Weight;
+
+    fn edges(&self, node: &N) -> impl IntoIterator<Item = (N, Self::Weight)>;
+}
+
+impl<N, I, F> Neighbours<N> for F
+where
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    fn neighbours(&self, node: &N) -> impl IntoIterator<Item = N> {
+        self(node)
+    }
+}
+
+impl<N, W, I, F> Graph<N> for F
+where
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, W)>,
+{
+    type Weight = W;
+
+    fn edges(&self, node: &N) -> impl IntoIterator<Item = (N, W)> {
+        self(node)
+    }
+}
+
+impl<N: Eq + Hash + Clone, S: BuildHasher> Neighbours<N> for HashMap<N, Vec<N>, S> {
+    fn neighbours(&self, node: &N) -> impl IntoIterator<Item = N> {
+        self.get(node).into_iter().flatten().cloned()
+    }
+}
+
+impl<N: Eq + Hash + Clone, W: Clone, S: BuildHasher> Graph<N> for HashMap<N, Vec<(N, W)>, S> {
+    type Weight = W;
+
+    fn edges(&self, node: &N) -> impl IntoIterator<Item = (N, W)> {
+        self.get(node).into_iter().flatten().cloned()
+    }
+}
+
+impl Neighbours<usize> for Vec<Vec<usize>> {
+    fn neighbours(&self, node: &usize) -> impl IntoIterator<Item = usize> {
+        self.get(*node).into_iter().flatten().copied()
+    }
+}
+
+impl<W: Clone> Graph<usize> for Vec<Vec<(usize, W)>> {
+    type Weight = W;
+
+    fn edges(&self, node: &usize) -> impl IntoIterator<Item = (usize, W)> {
+        self.get(*node).into_iter().flatten().cloned()
+    }
+}
+
+/// A rectangular grid whose nodes are the `Location`s of its cells, connected orthogonally.
+///
+/// `step` decides whether (and for `Graph`, at what cost) one may move between two adjacent cells.
+pub struct Grid<'a, T, F> {
+    cells: &'a Vec<Vec<T>>,
+    step: F,
+}
+
+impl<'a, T, F> Grid<'a, T, F> {
+    pub fn new(cells: &'a Vec<Vec<T>>, step: F) -> Self {
+        Self { cells, step }
+    }
+
+    fn adjacent(&self, node: &Location<i32>) -> impl Iterator<Item = (Location<i32>, &T, &T)> {
+        let from = self.cells.get_2d(*node);
+
+        node.iter_adjacent().into_iter().filter_map(move |next| {
+            let to = self.cells.get_2d(next)?;
+            Some((next, from?, to))
+        })
+    }
+}
+
+impl<T, F> Neighbours<Location<i32>> for Grid<'_, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    fn neighbours(&self, node: &Location<i32>) -> impl IntoIterator<Item = Location<i32>> {
+        self.adjacent(node)
+            .filter(|(_, from, to)| (self.step)(from, to))
+            .map(|(next, _, _)| next)
+    }
+}
+
+impl<T, W, F> Graph<Location<i32>> for Grid<'_, T, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type Weight = W;
+
+    fn edges(&self, node: &Location<i32>) -> impl IntoIterator<Item = (Location<i32>, W)> {
+        self.adjacent(node)
+            .filter_map(|(next, from, to)| Some((next, (self.step)(from, to)?)))
+    }
+}
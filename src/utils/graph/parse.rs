@@ -0,0 +1,130 @@
+use nom::Parser;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, space0, space1};
+use nom::error::ParseError;
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, separated_pair};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Directed,
+    Undirected,
+}
+
+/// `a -> b, c`
+pub fn arrow_list<'a, N, E, F>(node: F) -> impl Parser<&'a str, Output = (N, Vec<N>), Error = E>
+where
+    F: Parser<&'a str, Output = N, Error = E> + Clone,
+    E: ParseError<&'a str>,
+{
+    separated_pair(
+        node.clone(),
+        delimited(space0, tag("->"), space0),
+        separated_list1((char(','), space0), node),
+    )
+}
+
+/// `a: b c d`
+pub fn colon_list<'a, N, E, F>(node: F) -> impl Parser<&'a str, Output = (N, Vec<N>), Error = E>
+where
+    F: Parser<&'a str, Output = N, Error = E> + Clone,
+    E: ParseError<&'a str>,
+{
+    separated_pair(
+        node.clone(),
+        (char(':'), space0),
+        separated_list1(space1, node),
+    )
+}
+
+/// `a-b`
+pub fn dash_edge<'a, N, E, F>(node: F) -> impl Parser<&'a str, Output = (N, N), Error = E>
+where
+    F: Parser<&'a str, Output = N, Error = E> + Clone,
+    E: ParseError<&'a str>,
+{
+    separated_pair(node.clone(), char('-'), node)
+}
+
+/// `a to b = 5`
+pub fn weighted_edge<'a, N, W, E, F, G>(
+    node: F,
+    weight: G,
+) -> impl Parser<&'a str, Output = (N, N, W), Error = E>
+where
+    F: Parser<&'a str, Output = N, Error = E> + Clone,
+    G: Parser<&'a str, Output = W, Error = E>,
+    E: ParseError<&'a str>,
+{
+    separated_pair(
+        separated_pair(node.clone(), (space1, tag("to"), space1), node),
+        delimited(space0, char('='), space0),
+        weight,
+    )
+    .map(|((from, to), weight)| (from, to, weight))
+}
+
+fn add_edge<N, V>(
+    adjacency: &mut HashMap<N, Vec<V>>,
+    from: N,
+    to: N,
+    value: V,
+    reverse: V,
+    direction: Direction,
+) where
+    N: Eq + Hash + Clone,
+{
+    adjacency.entry(from.clone()).or_default().push(value);
+    let back = adjacency.entry(to).or_default();
+    if direction == Direction::Undirected {
+        back.push(reverse);
+    }
+}
+
+/// Builds adjacency from `(node, successors)` lines; every node mentioned gets an entry.
+pub fn from_lists<N: Eq + Hash + Clone>(
+    lists: impl IntoIterator<Item = (N, Vec<N>)>,
+    direction: Direction,
+) -> HashMap<N, Vec<N>> {
+    from_edges(
+        lists
+            .into_iter()
+            .flat_map(|(from, to)| to.into_iter().map(move |to| (from.clone(), to))),
+        direction,
+    )
+}
+
+/// Builds adjacency from single edges; every node mentioned gets an entry.
+pub fn from_edges<N: Eq + Hash + Clone>(
+    edges: impl IntoIterator<Item = (N, N)>,
+    direction: Direction,
+) -> HashMap<N, Vec<N>> {
+    let mut adjacency = HashMap::new();
+    for (from, to) in edges {
+        add_edge(
+            &mut adjacency,
+            from.clone(),
+            to.clone(),
+            to,
+            from,
+            direction,
+        );
+    }
+    adjacency
+}
+
+/// Builds weighted adjacency from `(from, to, weight)` edges; every node mentioned gets an entry.
+pub fn from_weighted_edges<N: Eq + Hash + Clone, W: Clone>(
+    edges: impl IntoIterator<Item = (N, N, W)>,
+    direction: Direction,
+) -> HashMap<N, Vec<(N, W)>> {
+    let mut adjacency = HashMap::new();
+    for (from, to, weight) in edges {
+        let forward = (to.clone(), weight.clone());
+        let backward = (from.clone(), weight);
+        add_edge(&mut adjacency, from, to, forward, backward, direction);
+    }
+    adjacency
+}
@@ -0,0 +1,56 @@
+use crate::utils::graph::Neighbours;
+use num::{One, Zero};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Counts the paths from `from` that end in a node matching `is_target`, memoising per node.
+///
+/// Paths may pass through a target and continue to another one; each prefix ending in a target is
+/// counted. Returns `None` if a cycle is reachable from `from`, as the count would not be finite.
+pub fn count_dag_paths<N, G, C>(
+    graph: &G,
+    from: N,
+    mut is_target: impl FnMut(&N) -> bool,
+) -> Option<C>
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+    C: Zero + One + Clone,
+{
+    let mut counts: HashMap<N, C> = HashMap::new();
+    let mut on_stack = HashSet::from([from.clone()]);
+    let mut stack = vec![(from.clone(), successors(graph, &from), 0)];
+
+    while let Some((node, children, index)) = stack.last_mut() {
+        if let Some(child) = children.get(*index) {
+            *index += 1;
+
+            if counts.contains_key(child) {
+                continue;
+            }
+            if !on_stack.insert(child.clone()) {
+                return None;
+            }
+
+            let child = child.clone();
+            let next = successors(graph, &child);
+            stack.push((child, next, 0));
+            continue;
+        }
+
+        let own = if is_target(node) { C::one() } else { C::zero() };
+        let count = children
+            .iter()
+            .fold(own, |acc, child| acc + counts[child].clone());
+
+        on_stack.remove(node);
+        counts.insert(node.clone(), count);
+        stack.pop();
+    }
+
+    counts.remove(&from)
+}
+
+fn successors<N, G: Neighbours<N>>(graph: &G, node: &N) -> Vec<N> {
+    graph.neighbours(node).into_iter().collect()
+}
@@ -0,0 +1,405 @@
+use crate::utils::graph::paths::count_dag_paths;
+use crate::utils::graph::{Graph, Neighbours};
+use num::{One, Zero};
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Outcome of a search: the distance to every settled node, and the first target reached.
+///
+/// `predecessors` holds, for every reached node, all nodes it can be reached from along a
+/// shortest path, the first of them forming a shortest-path tree. When the search stops early at
+/// a target, it still settles every queued node that could lie on an equally short path, so the
+/// predecessors of the target are complete while `distances` may miss farther nodes.
+///
+/// A cycle of zero-weight edges along shortest paths shows up as a cycle in `predecessors`; there
+/// are then infinitely many shortest walks and `count_paths` returns `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Search<N: Eq + Hash, W> {
+    pub distances: HashMap<N, W>,
+    pub predecessors: HashMap<N, Vec<N>>,
+    pub target: Option<(N, W)>,
+}
+
+impl<N: Eq + Hash + Clone, W> Search<N, W> {
+    pub fn distance(&self, node: &N) -> Option<&W> {
+        self.distances.get(node)
+    }
+
+    pub fn target_cost(&self) -> Option<&W> {
+        self.target.as_ref().map(|(_, cost)| cost)
+    }
+
+    fn predecessors_of(&self, node: &N) -> &[N] {
+        self.predecessors.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    /// One shortest path from a source to `node`, both ends included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.predecessors_of(path.last()?).first() {
+            path.push(previous.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every shortest path to `node` as a DAG, mapping each node on them to its successors.
+    pub fn shortest_path_dag(&self, node: &N) -> HashMap<N, Vec<N>> {
+        let mut dag: HashMap<N, Vec<N>> = HashMap::new();
+        dag.insert(node.clone(), vec![]);
+
+        let mut stack = vec![node.clone()];
+        while let Some(current) = stack.pop() {
+            for previous in self.predecessors_of(&current) {
+                if !dag.contains_key(previous) {
+                    stack.push(previous.clone());
+                }
+                dag.entry(previous.clone())
+                    .or_default()
+                    .push(current.clone());
+            }
+        }
+
+        dag
+    }
+
+    /// All nodes lying on any shortest path to `node`, including the sources and `node` itself.
+    pub fn on_shortest_paths(&self, node: &N) -> HashSet<N> {
+        self.shortest_path_dag(node).into_keys().collect()
+    }
+
+    /// Number of distinct shortest paths to `node`; pick `u128` or a big integer for `C`.
+    pub fn count_paths<C: Zero + One + Clone>(&self, node: &N) -> Option<C> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let backwards = |current: &N| self.predecessors_of(current).to_vec();
+        count_dag_paths(&backwards, node.clone(), |current| {
+            self.predecessors_of(current).is_empty()
+        })
+    }
+}
+
+/// Breadth-first search counting steps, stopping at the first node matching `is_target`.
+///
+/// Pass `|_| false` to explore everything reachable from `sources`.
+pub fn bfs<N, G>(
+    graph: &G,
+    sources: impl IntoIterator<Item = N>,
+    mut is_target: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+{
+    let mut distances = HashMap::new();
+    let mut predecessors: HashMap<N, Vec<N>> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for source in sources {
+        if let Entry::Vacant(entry) = distances.entry(source.clone()) {
+            entry.insert(0);
+            queue.push_back((source, 0));
+        }
+    }
+
+    while let Some((node, distance)) = queue.pop_front() {
+        if is_target(&node) {
+            return Search {
+                distances,
+                predecessors,
+                target: Some((node, distance)),
+            };
+        }
+
+        for next in graph.neighbours(&node) {
+            match distances.entry(next.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(distance + 1);
+                    predecessors.insert(next.clone(), vec![node.clone()]);
+                    queue.push_back((next, distance + 1));
+                }
+                Entry::Occupied(entry) if *entry.get() == distance + 1 => {
+                    predecessors.entry(next).or_default().push(node.clone());
+                }
+                Entry::Occupied(_) => {}
+            }
+        }
+    }
+
+    Search {
+        distances,
+        predecessors,
+        target: None,
+    }
+}
+
+/// Shortest paths on a graph whose edge weights are all either zero or one.
+pub fn bfs_01<N, G>(
+    graph: &G,
+    sources: impl IntoIterator<Item = N>,
+    mut is_target: impl FnMut(&N) -> bool,
+) -> Search<N, G::Weight>
+where
+    N: Eq + Hash + Clone,
+    G: Graph<N>,
+    G::Weight: Zero + One + Ord + Copy,
+{
+    let mut best = HashMap::new();
+    let mut distances = HashMap::new();
+    let mut predecessors = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for source in sources {
+        best.insert(source.clone(), G::Weight::zero());
+        queue.push_back((source, G::Weight::zero()));
+    }
+
+    let mut target = None;
+    while let Some((node, cost)) = queue.pop_front() {
+        if target
+            .as_ref()
+            .is_some_and(|&(_, target_cost)| cost > target_cost)
+        {
+            break;
+        }
+        if distances.contains_key(&node) || best.get(&node).is_some_and(|&b| b < cost) {
+            continue;
+        }
+        distances.insert(node.clone(), cost);
+
+        if target.is_none() && is_target(&node) {
+            target = Some((node.clone(), cost));
+        }
+
+        for (next, weight) in graph.edges(&node) {
+            debug_assert!(
+                weight.is_zero() || weight.is_one(),
+                "bfs_01 needs 0/1 weights"
+            );
+
+            let next_cost = cost + weight;
+            if !relax(&mut best, &mut predecessors, &node, &next, next_cost) {
+                continue;
+            }
+
+            if weight.is_zero() {
+                queue.push_front((next, next_cost));
+            } else {
+                queue.push_back((next, next_cost));
+            }
+        }
+    }
+
+    Search {
+        distances,
+        predecessors,
+        target,
+    }
+}
+
+/// Dijkstra's shortest paths for non-negative weights.
+pub fn dijkstra<N, G>(
+    graph: &G,
+    sources: impl IntoIterator<Item = N>,
+    is_target: impl FnMut(&N) -> bool,
+) -> Search<N, G::Weight>
+where
+    N: Eq + Hash + Clone,
+    G: Graph<N>,
+    G::Weight: Zero + Ord + Copy,
+{
+    astar(graph, sources, is_target, |_| G::Weight::zero())
+}
+
+/// A* search, guided by a consistent `heuristic`: it never overestimates the remaining cost, and
+/// never drops by more than the weight of an edge along it.
+pub fn astar<N, G>(
+    graph: &G,
+    sources: impl IntoIterator<Item = N>,
+    mut is_target: impl FnMut(&N) -> bool,
+    heuristic: impl Fn(&N) -> G::Weight,
+) -> Search<N, G::Weight>
+where
+    N: Eq + Hash + Clone,
+    G: Graph<N>,
+    G::Weight: Zero + Ord + Copy,
+{
+    let mut best = HashMap::new();
+    let mut distances = HashMap::new();
+    let mut predecessors = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    for source in sources {
+        best.insert(source.clone(), G::Weight::zero());
+        queue.push(Queued {
+            priority: heuristic(&source),
+            cost: G::Weight::zero(),
+            node: source,
+        });
+    }
+
+    let mut target = None;
+    while let Some(Queued {
+        priority,
+        cost,
+        node,
+    }) = queue.pop()
+    {
+        // Anything queued at the target's cost could still be another way into it.
+        if target
+            .as_ref()
+            .is_some_and(|&(_, target_cost)| priority > target_cost)
+        {
+            break;
+        }
+        if distances.contains_key(&node) || best.get(&node).is_some_and(|&b| b < cost) {
+            continue;
+        }
+        distances.insert(node.clone(), cost);
+
+        if target.is_none() && is_target(&node) {
+            target = Some((node.clone(), cost));
+        }
+
+        for (next, weight) in graph.edges(&node) {
+            let next_cost = cost + weight;
+            if !relax(&mut best, &mut predecessors, &node, &next, next_cost) {
+                continue;
+            }
+
+            queue.push(Queued {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    Search {
+        distances,
+        predecessors,
+        target,
+    }
+}
+
+/// Records `next_cost` as a way to reach `next` from `node`, returning whether it improved on the
+/// best known cost (and so `next` needs to be queued again).
+///
+/// Equal-cost ways in are kept even once `next` is settled, as a zero-weight edge can reach a node
+/// of the same distance that happened to be settled first.
+fn relax<N, W>(
+    best: &mut HashMap<N, W>,
+    predecessors: &mut HashMap<N, Vec<N>>,
+    node: &N,
+    next: &N,
+    next_cost: W,
+) -> bool
+where
+    N: Eq + Hash + Clone,
+    W: Ord + Copy,
+{
+    match best.get(next).map(|best| next_cost.cmp(best)) {
+        Some(Ordering::Greater) => false,
+        Some(Ordering::Equal) => {
+            // Sources have no entry and stay roots, even when a zero-weight edge leads back.
+            if let Some(previous) = predecessors.get_mut(next) {
+                previous.push(node.clone());
+            }
+            false
+        }
+        Some(Ordering::Less) | None => {
+            best.insert(next.clone(), next_cost);
+            predecessors.insert(next.clone(), vec![node.clone()]);
+            true
+        }
+    }
+}
+
+/// Heap entry ordered by `priority` alone, smallest first.
+struct Queued<N, W> {
+    priority: W,
+    cost: W,
+    node: N,
+}
+
+impl<N, W: Ord> PartialEq for Queued<N, W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, W: Ord> Eq for Queued<N, W> {}
+
+impl<N, W: Ord> PartialOrd for Queued<N, W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, W: Ord> Ord for Queued<N, W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::graph::Grid;
+    use crate::utils::location::Location;
+
+    #[test]
+    fn test_equal_cost_predecessor_through_zero_weight_edge() {
+        let graph: Vec<Vec<(usize, u32)>> = vec![vec![(2, 1), (1, 1)], vec![(2, 0)], vec![]];
+
+        let search = dijkstra(&graph, [0], |_| false);
+        assert_eq!(search.count_paths::<u64>(&2), Some(2));
+        assert_eq!(search.on_shortest_paths(&2), HashSet::from([0, 1, 2]));
+
+        let search = bfs_01(&graph, [0], |_| false);
+        assert_eq!(search.count_paths::<u64>(&2), Some(2));
+        assert_eq!(search.path_to(&2).map(|path| path.len()), Some(2));
+    }
+
+    #[test]
+    fn test_zero_weight_cycle() {
+        let graph: Vec<Vec<(usize, u32)>> = vec![vec![(1, 0)], vec![(2, 0)], vec![(1, 0), (0, 0)]];
+
+        let search = bfs_01(&graph, [0], |_| false);
+        assert_eq!(search.path_to(&2), Some(vec![0, 1, 2]));
+        assert_eq!(search.count_paths::<u64>(&2), None);
+    }
+
+    #[test]
+    fn test_early_stop_keeps_all_predecessors() {
+        let cells = vec![vec![(); 10]; 10];
+        let grid = Grid::new(&cells, |_: &(), _: &()| Some(1u32));
+        let (start, end) = (Location::new(0, 0), Location::new(9, 9));
+
+        let full = dijkstra(&grid, [start], |_| false);
+        assert_eq!(full.count_paths::<u64>(&end), Some(48620));
+
+        let stopped = dijkstra(&grid, [start], |&node| node == end);
+        assert_eq!(stopped.target_cost(), Some(&18));
+        assert_eq!(stopped.count_paths::<u64>(&end), Some(48620));
+
+        let guided = astar(
+            &grid,
+            [start],
+            |&node| node == end,
+            |&node| node.manhattan_distance(end) as u32,
+        );
+        assert_eq!(guided.target_cost(), Some(&18));
+        assert_eq!(guided.count_paths::<u64>(&end), Some(48620));
+        assert_eq!(guided.on_shortest_paths(&end).len(), 100);
+    }
+}
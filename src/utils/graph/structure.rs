@@ -0,0 +1,411 @@
+use crate::utils::graph::Neighbours;
+use crate::utils::interner::Interner;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Interns `nodes` and everything reachable from them, and builds the dense adjacency over it.
+fn index<N, G>(nodes: impl IntoIterator<Item = N>, graph: &G) -> (Interner<N>, Vec<Vec<usize>>)
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+{
+    let mut interner: Interner<N> = nodes.into_iter().collect();
+    let mut adjacency = vec![];
+
+    while adjacency.len() < interner.len() {
+        let node = interner[adjacency.len()].clone();
+        let next = graph
+            .neighbours(&node)
+            .into_iter()
+            .map(|next| interner.intern(next))
+            .collect();
+        adjacency.push(next);
+    }
+
+    (interner, adjacency)
+}
+
+fn resolve<N: Eq + Hash + Clone>(interner: &Interner<N>, ids: Vec<usize>) -> Vec<N> {
+    ids.into_iter().map(|id| interner[id].clone()).collect()
+}
+
+trait Frontier {
+    fn push(&mut self, id: usize);
+    fn pop(&mut self) -> Option<usize>;
+}
+
+impl Frontier for VecDeque<usize> {
+    fn push(&mut self, id: usize) {
+        self.push_back(id);
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.pop_front()
+    }
+}
+
+/// Pops the ready node with the lowest rank, where ranks are precomputed from the sort keys.
+struct ByRank {
+    ranks: Vec<usize>,
+    heap: BinaryHeap<Reverse<(usize, usize)>>,
+}
+
+impl Frontier for ByRank {
+    fn push(&mut self, id: usize) {
+        self.heap.push(Reverse((self.ranks[id], id)));
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.heap.pop().map(|Reverse((_, id))| id)
+    }
+}
+
+fn kahn(adjacency: &[Vec<usize>], mut frontier: impl Frontier) -> Result<Vec<usize>, Vec<usize>> {
+    let mut in_degree = vec![0; adjacency.len()];
+    for &next in adjacency.iter().flatten() {
+        in_degree[next] += 1;
+    }
+
+    for (id, _) in in_degree.iter().enumerate().filter(|&(_, &d)| d == 0) {
+        frontier.push(id);
+    }
+
+    let mut order = Vec::with_capacity(adjacency.len());
+    while let Some(id) = frontier.pop() {
+        order.push(id);
+
+        for &next in &adjacency[id] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                frontier.push(next);
+            }
+        }
+    }
+
+    if order.len() == adjacency.len() {
+        Ok(order)
+    } else {
+        Err(dense_cycle(adjacency).unwrap_or_default())
+    }
+}
+
+/// Kahn's topological sort, emitting ready nodes in the order they were first seen.
+///
+/// Nodes reachable from `nodes` are included even if not listed. On a cycle, returns a witness
+/// cycle as the error.
+pub fn topological_sort<N, G>(
+    nodes: impl IntoIterator<Item = N>,
+    graph: &G,
+) -> Result<Vec<N>, Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+{
+    let (interner, adjacency) = index(nodes, graph);
+
+    kahn(&adjacency, VecDeque::new())
+        .map(|order| resolve(&interner, order))
+        .map_err(|cycle| resolve(&interner, cycle))
+}
+
+/// Kahn's topological sort that always emits the ready node with the smallest key next.
+///
+/// `topological_sort_by_key(nodes, graph, |node| node.clone())` gives the lexicographically
+/// smallest order.
+pub fn topological_sort_by_key<N, G, K>(
+    nodes: impl IntoIterator<Item = N>,
+    graph: &G,
+    key: impl Fn(&N) -> K,
+) -> Result<Vec<N>, Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+    K: Ord,
+{
+    let (interner, adjacency) = index(nodes, graph);
+
+    let mut ids = (0..interner.len()).collect::<Vec<_>>();
+    ids.sort_by_cached_key(|&id| key(&interner[id]));
+
+    let mut ranks = vec![0; ids.len()];
+    for (rank, id) in ids.into_iter().enumerate() {
+        ranks[id] = rank;
+    }
+
+    let frontier = ByRank {
+        ranks,
+        heap: BinaryHeap::new(),
+    };
+
+    kahn(&adjacency, frontier)
+        .map(|order| resolve(&interner, order))
+        .map_err(|cycle| resolve(&interner, cycle))
+}
+
+fn dense_cycle(adjacency: &[Vec<usize>]) -> Option<Vec<usize>> {
+    const WHITE: u8 = 0;
+    const GREY: u8 = 1;
+    const BLACK: u8 = 2;
+
+    let mut colour = vec![WHITE; adjacency.len()];
+
+    for root in 0..adjacency.len() {
+        if colour[root] != WHITE {
+            continue;
+        }
+
+        colour[root] = GREY;
+        let mut stack = vec![(root, 0)];
+
+        while let Some((node, index)) = stack.last_mut() {
+            let node = *node;
+            let Some(&next) = adjacency[node].get(*index) else {
+                colour[node] = BLACK;
+                stack.pop();
+                continue;
+            };
+            *index += 1;
+
+            match colour[next] {
+                WHITE => {
+                    colour[next] = GREY;
+                    stack.push((next, 0));
+                }
+                GREY => {
+                    let start = stack.iter().position(|&(id, _)| id == next)?;
+                    return Some(stack[start..].iter().map(|&(id, _)| id).collect());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds some cycle, listed in edge order; the last node has an edge back to the first.
+pub fn find_cycle<N, G>(nodes: impl IntoIterator<Item = N>, graph: &G) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+{
+    let (interner, adjacency) = index(nodes, graph);
+    dense_cycle(&adjacency).map(|cycle| resolve(&interner, cycle))
+}
+
+/// Tarjan's algorithm, iteratively; components come out sinks first (reverse topological order).
+fn dense_components(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut counter = 0;
+    let mut indices: Vec<Option<usize>> = vec![None; adjacency.len()];
+    let mut low_links = vec![0; adjacency.len()];
+    let mut on_stack = vec![false; adjacency.len()];
+    let mut stack = vec![];
+    let mut components = vec![];
+
+    for root in 0..adjacency.len() {
+        if indices[root].is_some() {
+            continue;
+        }
+
+        let mut calls = vec![(root, 0)];
+        indices[root] = Some(counter);
+        low_links[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, index)) = calls.last_mut() {
+            let node = *node;
+
+            if let Some(&next) = adjacency[node].get(*index) {
+                *index += 1;
+
+                match indices[next] {
+                    None => {
+                        indices[next] = Some(counter);
+                        low_links[next] = counter;
+                        counter += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        calls.push((next, 0));
+                    }
+                    Some(next_index) if on_stack[next] => {
+                        low_links[node] = low_links[node].min(next_index);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low_links[parent] = low_links[parent].min(low_links[node]);
+            }
+
+            if Some(low_links[node]) == indices[node] {
+                let mut component = vec![];
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// Strongly connected components, sinks first (reverse topological order of the condensation).
+pub fn strongly_connected_components<N, G>(
+    nodes: impl IntoIterator<Item = N>,
+    graph: &G,
+) -> Vec<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+{
+    let (interner, adjacency) = index(nodes, graph);
+
+    dense_components(&adjacency)
+        .into_iter()
+        .map(|component| resolve(&interner, component))
+        .collect()
+}
+
+/// The DAG of strongly connected components.
+///
+/// Components are numbered in topological order, and `edges` is itself a graph over those
+/// numbers, so it can be fed back into the other algorithms.
+#[derive(Debug, Clone)]
+pub struct Condensation<N: Eq + Hash> {
+    pub components: Vec<Vec<N>>,
+    pub component_of: HashMap<N, usize>,
+    pub edges: Vec<Vec<usize>>,
+}
+
+pub fn condensation<N, G>(nodes: impl IntoIterator<Item = N>, graph: &G) -> Condensation<N>
+where
+    N: Eq + Hash + Clone,
+    G: Neighbours<N>,
+{
+    let (interner, adjacency) = index(nodes, graph);
+
+    let mut components = dense_components(&adjacency);
+    components.reverse();
+
+    let mut component_of = vec![0; adjacency.len()];
+    for (number, component) in components.iter().enumerate() {
+        for &id in component {
+            component_of[id] = number;
+        }
+    }
+
+    let mut edges = vec![vec![]; components.len()];
+    for (id, next) in adjacency.iter().enumerate() {
+        for &next in next {
+            let (from, to) = (component_of[id], component_of[next]);
+            if from != to {
+                edges[from].push(to);
+            }
+        }
+    }
+    for next in &mut edges {
+        next.sort_unstable();
+        next.dedup();
+    }
+
+    Condensation {
+        component_of: interner
+            .iter()
+            .map(|(id, node)| (node.clone(), component_of[id]))
+            .collect(),
+        components: components
+            .into_iter()
+            .map(|component| resolve(&interner, component))
+            .collect(),
+        edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut components: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+        components.iter_mut().for_each(|component| component.sort());
+        components
+    }
+
+    /// Two cycles, a cross edge into an already finished component, and a source joining both.
+    fn tangled() -> Vec<Vec<usize>> {
+        vec![
+            vec![3, 1],
+            vec![2],
+            vec![0],
+            vec![4],
+            vec![5],
+            vec![3, 6],
+            vec![],
+            vec![3, 0],
+        ]
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = vec![vec![], vec![], vec![3], vec![1], vec![0, 1], vec![2, 0]];
+
+        let order = topological_sort(0..6, &graph).unwrap();
+        for (node, next) in graph.iter().enumerate() {
+            let position = |n| order.iter().position(|&o| o == n);
+            assert!(next.iter().all(|&n| position(node) < position(n)));
+        }
+
+        assert_eq!(
+            topological_sort_by_key(0..6, &graph, |&node| node),
+            Ok(vec![4, 5, 0, 2, 3, 1])
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let graph = vec![vec![1], vec![2], vec![3, 1], vec![]];
+
+        let cycle = topological_sort([0], &graph).unwrap_err();
+        assert_eq!(sorted(vec![cycle]), vec![vec![1, 2]]);
+        assert_eq!(find_cycle([0], &graph), Some(vec![1, 2]));
+        assert_eq!(find_cycle([0], &vec![vec![1], vec![]]), None);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let components = strongly_connected_components(0..8, &tangled());
+        assert_eq!(
+            sorted(components),
+            vec![vec![6], vec![3, 4, 5], vec![0, 1, 2], vec![7]]
+        );
+    }
+
+    #[test]
+    fn test_condensation() {
+        let condensation = condensation(0..8, &tangled());
+
+        assert_eq!(
+            sorted(condensation.components),
+            vec![vec![7], vec![0, 1, 2], vec![3, 4, 5], vec![6]]
+        );
+        assert_eq!(condensation.component_of[&4], 2);
+        assert_eq!(
+            condensation.edges,
+            vec![vec![1, 2], vec![2], vec![3], vec![]]
+        );
+        assert_eq!(
+            topological_sort(0..4, &condensation.edges),
+            Ok(vec![0, 1, 2, 3])
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use crate::utils::linear_system::Rational;
+use num::{Integer, One, Signed, Zero};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    AtMost,
+    AtLeast,
+}
+
+impl Relation {
+    fn flipped(self) -> Self {
+        match self {
+            Relation::Equal => Relation::Equal,
+            Relation::AtMost => Relation::AtLeast,
+            Relation::AtLeast => Relation::AtMost,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Constraint {
+    coefficients: Vec<Rational>,
+    relation: Relation,
+    rhs: Rational,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Optimal {
+        values: Vec<T>,
+        objective: T,
+    },
+    Infeasible,
+    Unbounded,
+    /// The relaxation is fractional, so branching is needed, but these variables have no upper
+    /// bound and the search might never end; cap them with [`Ilp::bounded`].
+    NeedsBounds(Vec<usize>),
+}
+
+impl<T> Outcome<T> {
+    pub fn values(&self) -> Option<&[T]> {
+        match self {
+            Outcome::Optimal { values, .. } => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn objective(&self) -> Option<&T> {
+        match self {
+            Outcome::Optimal { objective, .. } => Some(objective),
+            _ => None,
+        }
+    }
+}
+
+/// Integer linear program over non-negative variables, solved exactly with the simplex method and
+/// branch-and-bound.
+///
+/// Pivots follow Bland's rule and branches always split the first fractional variable, so the
+/// same program always gives the same answer.
+#[derive(Debug, Clone)]
+pub struct Ilp {
+    variables: usize,
+    objective: Vec<Rational>,
+    maximise: bool,
+    constraints: Vec<Constraint>,
+}
+
+impl Ilp {
+    /// A program over `variables` variables, each at least zero, with a zero objective.
+    pub fn new(variables: usize) -> Self {
+        Self {
+            variables,
+            objective: vec![Rational::zero(); variables],
+            maximise: false,
+            constraints: vec![],
+        }
+    }
+
+    fn row(&self, coefficients: impl IntoIterator<Item = i128>) -> Vec<Rational> {
+        let row = coefficients
+            .into_iter()
+            .map(Rational::from_integer)
+            .collect::<Vec<_>>();
+        assert_eq!(row.len(), self.variables, "one coefficient per variable");
+        row
+    }
+
+    pub fn minimise(mut self, coefficients: impl IntoIterator<Item = i128>) -> Self {
+        self.objective = self.row(coefficients);
+        self.maximise = false;
+        self
+    }
+
+    pub fn maximise(mut self, coefficients: impl IntoIterator<Item = i128>) -> Self {
+        self.objective = self.row(coefficients);
+        self.maximise = true;
+        self
+    }
+
+    pub fn constraint(
+        mut self,
+        coefficients: impl IntoIterator<Item = i128>,
+        relation: Relation,
+        rhs: i128,
+    ) -> Self {
+        let coefficients = self.row(coefficients);
+        self.constraints.push(Constraint {
+            coefficients,
+            relation,
+            rhs: Rational::from_integer(rhs),
+        });
+        self
+    }
+
+    pub fn equal(self, coefficients: impl IntoIterator<Item = i128>, rhs: i128) -> Self {
+        self.constraint(coefficients, Relation::Equal, rhs)
+    }
+
+    pub fn at_most(self, coefficients: impl IntoIterator<Item = i128>, rhs: i128) -> Self {
+        self.constraint(coefficients, Relation::AtMost, rhs)
+    }
+
+    pub fn at_least(self, coefficients: impl IntoIterator<Item = i128>, rhs: i128) -> Self {
+        self.constraint(coefficients, Relation::AtLeast, rhs)
+    }
+
+    /// Caps `variable` at `max`, which branching needs if no constraint bounds it already.
+    pub fn bounded(self, variable: usize, max: i128) -> Self {
+        let mut unit = vec![0; self.variables];
+        unit[variable] = 1;
+        self.at_most(unit, max)
+    }
+
+    /// The objective to minimise, negated when maximising.
+    fn costs(&self) -> Vec<Rational> {
+        if self.maximise {
+            self.objective.iter().map(|&c| -c).collect()
+        } else {
+            self.objective.clone()
+        }
+    }
+
+    fn evaluate(&self, values: &[Rational]) -> Rational {
+        self.objective
+            .iter()
+            .zip(values)
+            .map(|(&c, &x)| c * x)
+            .sum()
+    }
+
+    /// Optimum of the linear relaxation, where variables may take fractional values.
+    pub fn solve_relaxation(&self) -> Outcome<Rational> {
+        match simplex(&self.costs(), &self.constraints) {
+            Outcome::Optimal { values, .. } => Outcome::Optimal {
+                objective: self.evaluate(&values),
+                values,
+            },
+            other => other,
+        }
+    }
+
+    /// Variables the constraints don't bound from above; branching only ends without them.
+    fn unbounded_variables(&self) -> Vec<usize> {
+        (0..self.variables)
+            .filter(|&variable| {
+                let mut costs = vec![Rational::zero(); self.variables];
+                costs[variable] = -Rational::one();
+                simplex(&costs, &self.constraints) == Outcome::Unbounded
+            })
+            .collect()
+    }
+
+    /// Whether some equality has no integer solution at all, its coefficients sharing a factor
+    /// that does not divide the right-hand side.
+    fn indivisible(&self) -> bool {
+        self.constraints
+            .iter()
+            .filter(|constraint| constraint.relation == Relation::Equal)
+            .any(|constraint| {
+                let gcd = constraint
+                    .coefficients
+                    .iter()
+                    .fold(0, |gcd: i128, c| gcd.gcd(&c.to_integer()));
+                gcd != 0 && constraint.rhs.to_integer() % gcd != 0
+            })
+    }
+
+    /// Optimum over integer values of the variables.
+    ///
+    /// Once the relaxation is fractional, branching needs every variable bounded from above, by
+    /// the constraints or by [`Ilp::bounded`], and reports `NeedsBounds` otherwise. An unbounded
+    /// relaxation is reported as `Unbounded` even if no integer point exists.
+    pub fn solve(&self) -> Outcome<i128> {
+        if self.indivisible() {
+            return Outcome::Infeasible;
+        }
+
+        let costs = self.costs();
+        let mut best: Option<(Rational, Vec<Rational>)> = None;
+        let mut checked_bounds = false;
+        // Branching bounds added on top of the constraints, one list per open node.
+        let mut stack = vec![vec![]];
+
+        while let Some(bounds) = stack.pop() {
+            let constraints = self
+                .constraints
+                .iter()
+                .chain(&bounds)
+                .cloned()
+                .collect::<Vec<_>>();
+            let (values, cost) = match simplex(&costs, &constraints) {
+                Outcome::Optimal { values, objective } => (values, objective),
+                Outcome::Infeasible => continue,
+                Outcome::Unbounded => return Outcome::Unbounded,
+                Outcome::NeedsBounds(_) => unreachable!("the simplex method doesn't branch"),
+            };
+
+            // Integer costs make every integer solution cost an integer, so rounding up is sound.
+            if let Some((best_cost, _)) = &best
+                && cost.ceil() >= *best_cost
+            {
+                continue;
+            }
+
+            let Some(variable) = values.iter().position(|value| !value.is_integer()) else {
+                best = Some((cost, values));
+                continue;
+            };
+
+            if !checked_bounds {
+                let unbounded = self.unbounded_variables();
+                if !unbounded.is_empty() {
+                    return Outcome::NeedsBounds(unbounded);
+                }
+                checked_bounds = true;
+            }
+
+            let mut unit = vec![Rational::zero(); self.variables];
+            unit[variable] = Rational::one();
+
+            // Pushed in reverse, so the rounded-down branch is explored first.
+            for (relation, rhs) in [
+                (Relation::AtLeast, values[variable].ceil()),
+                (Relation::AtMost, values[variable].floor()),
+            ] {
+                let mut next = bounds.clone();
+                next.push(Constraint {
+                    coefficients: unit.clone(),
+                    relation,
+                    rhs,
+                });
+                stack.push(next);
+            }
+        }
+
+        match best {
+            Some((_, values)) => Outcome::Optimal {
+                objective: self.evaluate(&values).to_integer(),
+                values: values.iter().map(Rational::to_integer).collect(),
+            },
+            None => Outcome::Infeasible,
+        }
+    }
+}
+
+/// Dense simplex tableau: one row per constraint, the right-hand side in the last column.
+struct Tableau {
+    rows: Vec<Vec<Rational>>,
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    fn pivot(&mut self, row: usize, column: usize, objective: &mut [Rational]) {
+        let pivot = self.rows[row][column];
+        for value in &mut self.rows[row] {
+            *value /= pivot;
+        }
+
+        let pivot_row = self.rows[row].clone();
+        let others = self
+            .rows
+            .iter_mut()
+            .enumerate()
+            .filter(|&(r, _)| r != row)
+            .map(|(_, other)| other.as_mut_slice())
+            .chain([objective]);
+        for other in others {
+            let factor = other[column];
+            if factor.is_zero() {
+                continue;
+            }
+            for (value, &pivot_value) in other.iter_mut().zip(&pivot_row) {
+                *value -= factor * pivot_value;
+            }
+        }
+
+        self.basis[row] = column;
+    }
+
+    /// Reduced costs of `costs` for the current basis, with the negated objective value last.
+    fn reduced(&self, costs: &[Rational]) -> Vec<Rational> {
+        let mut objective = costs.to_vec();
+        objective.push(Rational::zero());
+
+        for (row, &basic) in self.rows.iter().zip(&self.basis) {
+            let cost = costs[basic];
+            if cost.is_zero() {
+                continue;
+            }
+            for (value, &entry) in objective.iter_mut().zip(row) {
+                *value -= cost * entry;
+            }
+        }
+        objective
+    }
+
+    /// Pivots until no column before `allowed` has a negative reduced cost, using Bland's rule.
+    /// Returns `false` if the objective is unbounded.
+    fn optimise(&mut self, objective: &mut [Rational], allowed: usize) -> bool {
+        let rhs = objective.len() - 1;
+        loop {
+            let Some(entering) = (0..allowed).find(|&column| objective[column].is_negative())
+            else {
+                return true;
+            };
+
+            let leaving = (0..self.rows.len())
+                .filter(|&row| self.rows[row][entering].is_positive())
+                .min_by_key(|&row| {
+                    (
+                        self.rows[row][rhs] / self.rows[row][entering],
+                        self.basis[row],
+                    )
+                });
+            let Some(leaving) = leaving else {
+                return false;
+            };
+
+            self.pivot(leaving, entering, objective);
+        }
+    }
+}
+
+/// Two-phase simplex minimising `costs · x` subject to the constraints and `x ≥ 0`.
+fn simplex(costs: &[Rational], constraints: &[Constraint]) -> Outcome<Rational> {
+    let variables = costs.len();
+    let constraints = constraints
+        .iter()
+        .map(|constraint| {
+            if constraint.rhs.is_negative() {
+                Constraint {
+                    coefficients: constraint.coefficients.iter().map(|&c| -c).collect(),
+                    relation: constraint.relation.flipped(),
+                    rhs: -constraint.rhs,
+                }
+            } else {
+                constraint.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let slacks = constraints
+        .iter()
+        .filter(|constraint| constraint.relation != Relation::Equal)
+        .count();
+    let artificials = constraints
+        .iter()
+        .filter(|constraint| constraint.relation != Relation::AtMost)
+        .count();
+    let real = variables + slacks;
+    let columns = real + artificials;
+
+    let mut tableau = Tableau {
+        rows: vec![],
+        basis: vec![],
+    };
+    let (mut slack, mut artificial) = (variables, real);
+    for constraint in &constraints {
+        let mut row = constraint.coefficients.clone();
+        row.resize(columns, Rational::zero());
+        row.push(constraint.rhs);
+
+        match constraint.relation {
+            Relation::AtMost => {
+                row[slack] = Rational::one();
+                tableau.basis.push(slack);
+                slack += 1;
+            }
+            Relation::AtLeast => {
+                row[slack] = -Rational::one();
+                slack += 1;
+                row[artificial] = Rational::one();
+                tableau.basis.push(artificial);
+                artificial += 1;
+            }
+            Relation::Equal => {
+                row[artificial] = Rational::one();
+                tableau.basis.push(artificial);
+                artificial += 1;
+            }
+        }
+        tableau.rows.push(row);
+    }
+
+    if artificials > 0 {
+        let mut phase_one = vec![Rational::zero(); columns];
+        phase_one[real..].fill(Rational::one());
+
+        let mut objective = tableau.reduced(&phase_one);
+        tableau.optimise(&mut objective, columns);
+        if !objective[columns].is_zero() {
+            return Outcome::Infeasible;
+        }
+
+        // Drive the artificials left in the basis (all at zero) out, dropping redundant rows.
+        let mut row = 0;
+        while row < tableau.rows.len() {
+            if tableau.basis[row] < real {
+                row += 1;
+                continue;
+            }
+            match (0..real).find(|&column| !tableau.rows[row][column].is_zero()) {
+                Some(column) => {
+                    tableau.pivot(row, column, &mut objective);
+                    row += 1;
+                }
+                None => {
+                    tableau.rows.swap_remove(row);
+                    tableau.basis.swap_remove(row);
+                }
+            }
+        }
+    }
+
+    let mut phase_two = costs.to_vec();
+    phase_two.resize(columns, Rational::zero());
+    let mut objective = tableau.reduced(&phase_two);
+    if !tableau.optimise(&mut objective, real) {
+        return Outcome::Unbounded;
+    }
+
+    let mut values = vec![Rational::zero(); variables];
+    for (row, &basic) in tableau.rows.iter().zip(&tableau.basis) {
+        if basic < variables {
+            values[basic] = row[columns];
+        }
+    }
+
+    Outcome::Optimal {
+        values,
+        objective: -objective[columns],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fewest presses of `buttons`, each adding one to the listed counters, to reach `targets`.
+    fn presses(buttons: &[&[usize]], targets: &[i128]) -> Outcome<i128> {
+        let mut program = Ilp::new(buttons.len()).minimise(vec![1; buttons.len()]);
+        for (counter, &target) in targets.iter().enumerate() {
+            let row = buttons
+                .iter()
+                .map(|button| button.contains(&counter) as i128);
+            program = program.equal(row, target);
+        }
+        program.solve()
+    }
+
+    #[test]
+    fn test_optimal() {
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        assert_eq!(presses(&buttons, &[3, 5, 4, 7]).objective(), Some(&10));
+
+        let buttons: [&[usize]; 5] = [&[0, 2, 3, 4], &[2, 3], &[0, 4], &[0, 1, 2], &[1, 2, 3, 4]];
+        assert_eq!(presses(&buttons, &[7, 5, 12, 7, 2]).objective(), Some(&12));
+
+        let buttons: [&[usize]; 4] = [&[0, 1, 2, 3, 4], &[0, 3, 4], &[0, 1, 2, 4, 5], &[1, 2]];
+        assert_eq!(
+            presses(&buttons, &[10, 11, 11, 5, 10, 5]).objective(),
+            Some(&11)
+        );
+    }
+
+    #[test]
+    fn test_maximise() {
+        let program = Ilp::new(2)
+            .maximise([3, 2])
+            .at_most([2, 1], 7)
+            .at_most([1, 3], 9);
+
+        assert_eq!(
+            program.solve_relaxation(),
+            Outcome::Optimal {
+                values: vec![Rational::new(12, 5), Rational::new(11, 5)],
+                objective: Rational::new(58, 5),
+            }
+        );
+        assert_eq!(
+            program.solve(),
+            Outcome::Optimal {
+                values: vec![3, 1],
+                objective: 11,
+            }
+        );
+    }
+
+    #[test]
+    fn test_negative_rhs() {
+        let program = Ilp::new(2).maximise([1, 2]).at_least([-1, -1], -4);
+        assert_eq!(program.solve().objective(), Some(&8));
+
+        let program = Ilp::new(2).minimise([0, 1]).at_most([1, -1], -2);
+        assert_eq!(
+            program.solve(),
+            Outcome::Optimal {
+                values: vec![0, 2],
+                objective: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_infeasible() {
+        let program = Ilp::new(2).at_most([1, 1], -1);
+        assert_eq!(program.solve_relaxation(), Outcome::Infeasible);
+        assert_eq!(program.solve(), Outcome::Infeasible);
+    }
+
+    #[test]
+    fn test_integer_infeasible() {
+        let program = Ilp::new(2).equal([1, 1], 1).equal([1, -1], 0);
+        assert!(program.solve_relaxation().values().is_some());
+        assert_eq!(program.solve(), Outcome::Infeasible);
+
+        let program = Ilp::new(2).minimise([0, 0]).equal([2, -2], 1);
+        assert!(program.solve_relaxation().values().is_some());
+        assert_eq!(program.solve(), Outcome::Infeasible);
+    }
+
+    #[test]
+    fn test_unbounded() {
+        assert_eq!(Ilp::new(1).maximise([1]).solve(), Outcome::Unbounded);
+        assert_eq!(
+            Ilp::new(2).minimise([-1, 0]).at_least([1, -1], 0).solve(),
+            Outcome::Unbounded
+        );
+    }
+
+    #[test]
+    fn test_bounded() {
+        let program = Ilp::new(2).minimise([1, 1]).equal([2, -3], 1);
+        assert_eq!(
+            program.bounded(0, 10).bounded(1, 10).solve(),
+            Outcome::Optimal {
+                values: vec![2, 1],
+                objective: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_branching_needs_bounds() {
+        let program = Ilp::new(2).minimise([1, 1]).equal([2, -3], 1);
+        assert_eq!(program.solve(), Outcome::NeedsBounds(vec![0, 1]));
+        assert_eq!(program.bounded(0, 10).solve().objective(), Some(&3));
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Index;
+
+/// Assigns dense ids `0..len` to values in insertion order, and maps them back.
+#[derive(Debug, Clone)]
+pub struct Interner<T: Eq + Hash + Clone> {
+    ids: HashMap<T, usize>,
+    values: Vec<T>,
+}
+
+impl<T: Eq + Hash + Clone> Default for Interner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash + Clone> Interner<T> {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Returns the id of `value`, assigning the next free one if it has not been seen yet.
+    pub fn intern(&mut self, value: T) -> usize {
+        if let Some(&id) = self.ids.get(&value) {
+            return id;
+        }
+
+        let id = self.values.len();
+        self.ids.insert(value.clone(), id);
+        self.values.push(value);
+        id
+    }
+
+    pub fn get(&self, value: &T) -> Option<usize> {
+        self.ids.get(value).copied()
+    }
+
+    pub fn resolve(&self, id: usize) -> Option<&T> {
+        self.values.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.values.iter().enumerate()
+    }
+}
+
+impl<T: Eq + Hash + Clone> Index<usize> for Interner<T> {
+    type Output = T;
+
+    fn index(&self, id: usize) -> &Self::Output {
+        &self.values[id]
+    }
+}
+
+impl<T: Eq + Hash + Clone> FromIterator<T> for Interner<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut interner = Interner::new();
+        for value in iter {
+            interner.intern(value);
+        }
+        interner
+    }
+}
@@ -0,0 +1,309 @@
+use crate::utils::Parsable;
+use nom::character::complete::char;
+use nom::sequence::separated_pair;
+use nom::{IResult, Parser};
+use num::PrimInt;
+use std::ops::RangeInclusive;
+
+/// Set of integers stored as sorted, disjoint, non-adjacent inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sorts and merges arbitrary `(start, end)` pairs, dropping empty ones.
+    fn normalise(mut ranges: Vec<(T, T)>) -> Self {
+        ranges.retain(|&(start, end)| start <= end);
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some((_, last)) if start <= last.saturating_add(T::one()) => {
+                    *last = (*last).max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// Number of disjoint ranges, not values; see [`IntervalSet::len`] for the latter.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Number of values covered, or `None` if that does not fit in `T`, as for the full domain.
+    pub fn len(&self) -> Option<T> {
+        self.ranges
+            .iter()
+            .try_fold(T::zero(), |total, &(start, end)| {
+                end.checked_sub(&start)?
+                    .checked_add(&T::one())?
+                    .checked_add(&total)
+            })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|&(start, _)| start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|&(_, end)| end)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let after = self.ranges.partition_point(|&(start, _)| start <= value);
+        after > 0 && value <= self.ranges[after - 1].1
+    }
+
+    /// The stored range containing `value`, if any.
+    pub fn range_containing(&self, value: T) -> Option<RangeInclusive<T>> {
+        let after = self.ranges.partition_point(|&(start, _)| start <= value);
+        let (start, end) = *self.ranges.get(after.checked_sub(1)?)?;
+        (value <= end).then_some(start..=end)
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // Everything from `first` to `last` overlaps or touches the new range.
+        let first = self
+            .ranges
+            .partition_point(|&(_, e)| e < start.saturating_sub(T::one()));
+        let last = self
+            .ranges
+            .partition_point(|&(s, _)| s <= end.saturating_add(T::one()));
+
+        let merged = if first < last {
+            (
+                start.min(self.ranges[first].0),
+                end.max(self.ranges[last - 1].1),
+            )
+        } else {
+            (start, end)
+        };
+
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|&(_, e)| e < start);
+        let last = self.ranges.partition_point(|&(s, _)| s <= end);
+        if first >= last {
+            return;
+        }
+
+        let (left, right) = (self.ranges[first].0, self.ranges[last - 1].1);
+        let mut kept = vec![];
+        if left < start {
+            kept.push((left, start - T::one()));
+        }
+        if right > end {
+            kept.push((end + T::one(), right));
+        }
+
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalise([self.ranges.as_slice(), other.ranges.as_slice()].concat())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut a, mut b) = (0, 0);
+
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(a), other.ranges.get(b))
+        {
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            if a_end < b_end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.remove(range);
+        }
+        result
+    }
+
+    /// Everything within `bounds` that is not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        let mut result = Self::new();
+        result.insert(bounds);
+        result.difference(self)
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        Self::normalise(iter.into_iter().map(RangeInclusive::into_inner).collect())
+    }
+}
+
+impl<T: PrimInt> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.extend(iter.into_iter().map(RangeInclusive::into_inner));
+        *self = Self::normalise(ranges);
+    }
+}
+
+impl<T> IntoIterator for IntervalSet<T> {
+    type Item = RangeInclusive<T>;
+    type IntoIter = std::iter::Map<std::vec::IntoIter<(T, T)>, fn((T, T)) -> RangeInclusive<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.into_iter().map(|(start, end)| start..=end)
+    }
+}
+
+/// A single `a-b` range.
+impl<'a, T: PrimInt + Parsable<'a>> Parsable<'a> for IntervalSet<T> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        separated_pair(T::parse, char('-'), T::parse)
+            .map(|(start, end)| Self::from_iter([start..=end]))
+            .parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The set holding exactly the marked values, built one value at a time.
+    fn from_model(model: &[bool; 256]) -> IntervalSet<u8> {
+        (u8::MIN..=u8::MAX)
+            .filter(|&value| model[value as usize])
+            .map(|value| value..=value)
+            .collect()
+    }
+
+    /// Pseudo-random ranges, weighted towards both ends of the domain.
+    fn ranges() -> impl Iterator<Item = RangeInclusive<u8>> {
+        let mut seed = 7u32;
+        std::iter::repeat_with(move || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let start = match seed >> 29 {
+                0 => 0,
+                1 => 250,
+                _ => (seed >> 8) as u8,
+            };
+            let length = (seed >> 20) as u8 % 12;
+            start..=start.saturating_add(length)
+        })
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set = IntervalSet::new();
+        let mut model = [false; 256];
+
+        for (step, range) in ranges().take(500).enumerate() {
+            let present = step % 3 != 2;
+            for value in range.clone() {
+                model[value as usize] = present;
+            }
+            if present {
+                set.insert(range);
+            } else {
+                set.remove(range);
+            }
+
+            assert_eq!(set, from_model(&model), "step {step}");
+        }
+    }
+
+    #[test]
+    fn test_queries() {
+        let set = IntervalSet::from_iter([10..=20, 0..=3, 4..=5, 30..=30, 18..=25]);
+
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![0..=5, 10..=25, 30..=30]
+        );
+        assert_eq!(set.range_count(), 3);
+        assert_eq!(set.len(), Some(23u8));
+        assert_eq!((set.min(), set.max()), (Some(0), Some(30)));
+        assert!(set.contains(25) && !set.contains(26) && !set.contains(u8::MAX));
+        assert_eq!(set.range_containing(12), Some(10..=25));
+        assert_eq!(set.range_containing(7), None);
+
+        let full = set.complement(0..=u8::MAX).union(&set);
+        assert_eq!(full.iter().collect::<Vec<_>>(), vec![0..=u8::MAX]);
+        assert_eq!(full.len(), None);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut values = ranges();
+        for _ in 0..50 {
+            let a = values.by_ref().take(4).collect::<IntervalSet<u8>>();
+            let b = values.by_ref().take(4).collect::<IntervalSet<u8>>();
+            let model = |f: fn(bool, bool) -> bool| {
+                let mut model = [false; 256];
+                for value in u8::MIN..=u8::MAX {
+                    model[value as usize] = f(a.contains(value), b.contains(value));
+                }
+                from_model(&model)
+            };
+
+            assert_eq!(a.union(&b), model(|x, y| x || y));
+            assert_eq!(a.intersection(&b), model(|x, y| x && y));
+            assert_eq!(a.difference(&b), model(|x, y| x && !y));
+            assert_eq!(
+                a.complement(20..=200),
+                model(|x, _| !x).intersection(&IntervalSet::from_iter([20..=200]))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            IntervalSet::<u64>::parse("3-5"),
+            Ok(("", IntervalSet::from_iter([3..=5])))
+        );
+    }
+}
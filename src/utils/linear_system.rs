@@ -0,0 +1,139 @@
+use num::rational::Ratio;
+use num::{One, Signed, Zero};
+
+pub type Rational = Ratio<i128>;
+
+/// Solution set of `A x = b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    /// Every solution is `particular + Σ tᵢ · basis[i]` for arbitrary `tᵢ`, where `basis[i]` has
+    /// a one at `free[i]` and zeros at the other free variables.
+    Parametric {
+        particular: Vec<Rational>,
+        basis: Vec<Vec<Rational>>,
+        free: Vec<usize>,
+    },
+    Inconsistent,
+}
+
+impl Solution {
+    pub fn unique(&self) -> Option<&[Rational]> {
+        match self {
+            Solution::Unique(solution) => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+/// Gauss-Jordan elimination of `matrix · x = rhs`, exactly over the rationals.
+pub fn solve(matrix: &[Vec<Rational>], rhs: &[Rational]) -> Solution {
+    assert_eq!(matrix.len(), rhs.len(), "one right-hand side per row");
+
+    let columns = matrix.first().map_or(0, Vec::len);
+    let mut rows = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, &value)| {
+            assert_eq!(row.len(), columns, "rows of equal length");
+            let mut row = row.clone();
+            row.push(value);
+            row
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivots = vec![];
+    for column in 0..columns {
+        let rank = pivots.len();
+        let Some(found) = (rank..rows.len()).find(|&r| !rows[r][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, found);
+
+        let pivot = rows[rank][column];
+        for value in &mut rows[rank] {
+            *value /= pivot;
+        }
+
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[column];
+            if r == rank || factor.is_zero() {
+                continue;
+            }
+            for (value, &pivot_value) in row.iter_mut().zip(&pivot_row).skip(column) {
+                *value -= factor * pivot_value;
+            }
+        }
+
+        pivots.push(column);
+    }
+
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[columns].is_zero())
+    {
+        return Solution::Inconsistent;
+    }
+
+    let mut particular = vec![Rational::zero(); columns];
+    for (row, &column) in pivots.iter().enumerate() {
+        particular[column] = rows[row][columns];
+    }
+
+    if pivots.len() == columns {
+        return Solution::Unique(particular);
+    }
+
+    let free = (0..columns)
+        .filter(|column| !pivots.contains(column))
+        .collect::<Vec<_>>();
+    let basis = free
+        .iter()
+        .map(|&free_column| {
+            let mut direction = vec![Rational::zero(); columns];
+            direction[free_column] = Rational::one();
+            for (row, &column) in pivots.iter().enumerate() {
+                direction[column] = -rows[row][free_column];
+            }
+            direction
+        })
+        .collect();
+
+    Solution::Parametric {
+        particular,
+        basis,
+        free,
+    }
+}
+
+/// [`solve`] for integer coefficients.
+pub fn solve_integers(matrix: &[Vec<i128>], rhs: &[i128]) -> Solution {
+    let matrix = matrix
+        .iter()
+        .map(|row| row.iter().copied().map(Rational::from_integer).collect())
+        .collect::<Vec<_>>();
+    let rhs = rhs
+        .iter()
+        .copied()
+        .map(Rational::from_integer)
+        .collect::<Vec<_>>();
+
+    solve(&matrix, &rhs)
+}
+
+pub fn is_integral(values: &[Rational]) -> bool {
+    values.iter().all(Rational::is_integer)
+}
+
+pub fn is_non_negative(values: &[Rational]) -> bool {
+    values.iter().all(|value| !value.is_negative())
+}
+
+/// The values as integers, if they all are.
+pub fn to_integers(values: &[Rational]) -> Option<Vec<i128>> {
+    values
+        .iter()
+        .map(|value| value.is_integer().then(|| value.to_integer()))
+        .collect()
+}
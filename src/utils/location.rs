@@ -9,7 +9,7 @@ use std::fmt::{Display, Formatter};
 use std::iter::{Sum, successors};
 use std::ops::{Add, Div, Mul, Neg, RangeInclusive, Rem, Sub};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Location<T: Num> {
     pub x: T,
     pub y: T,
@@ -227,11 +227,11 @@ impl<T: Num + Copy> From<(T, T)> for Location<T> {
 }
 
 pub trait Distance<T: Num + Copy> {
-    fn distance<U: Num + Copy + Pow<f32, Output = U> + From<T>>(self: &Self, other: &Self) -> U;
+    fn distance<U: Num + Copy + Pow<f32, Output = U> + From<T>>(&self, other: &Self) -> U;
 }
 
 impl<T: Num + Copy> Distance<T> for Location<T> {
-    fn distance<U: Num + Copy + Pow<f32, Output = U> + From<T>>(self: &Self, other: &Self) -> U {
+    fn distance<U: Num + Copy + Pow<f32, Output = U> + From<T>>(&self, other: &Self) -> U {
         let diff_x: U = U::from(self.x) - U::from(other.x);
         let diff_y: U = U::from(self.y) - U::from(other.y);
 
@@ -172,7 +172,7 @@ impl<T: Num + Copy> Sum for Location3<T> {
 }
 
 impl<T: Num + Copy> Distance<T> for Location3<T> {
-    fn distance<U: Num + Copy + Pow<f32, Output = U> + From<T>>(self: &Self, other: &Self) -> U {
+    fn distance<U: Num + Copy + Pow<f32, Output = U> + From<T>>(&self, other: &Self) -> U {
         let diff_x: U = U::from(self.x) - U::from(other.x);
         let diff_y: U = U::from(self.y) - U::from(other.y);
         let diff_z: U = U::from(self.z) - U::from(other.z);
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+/// Cache hits and misses; only counted in debug builds, and always zero in release builds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lookups = self.hits + self.misses;
+        let rate = if lookups == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f64 / lookups as f64
+        };
+        write!(
+            f,
+            "{} hits, {} misses ({rate:.1}% hit rate)",
+            self.hits, self.misses
+        )
+    }
+}
+
+/// Cache for recursive functions.
+///
+/// The recursive function takes `&mut Memo` and wraps its body in `get_or_compute`, whose closure
+/// receives the memo back so it can recurse through it.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    #[cfg(debug_assertions)]
+    stats: MemoStats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            #[cfg(debug_assertions)]
+            stats: MemoStats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key`, or computes it with `compute(self, &key)` and caches it.
+    ///
+    /// The cache is not borrowed while `compute` runs, so it can recurse through `self`.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self, &K) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            #[cfg(debug_assertions)]
+            {
+                self.stats.hits += 1;
+            }
+            return value.clone();
+        }
+
+        #[cfg(debug_assertions)]
+        {
+            self.stats.misses += 1;
+        }
+
+        let value = compute(self, &key);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        #[cfg(debug_assertions)]
+        return self.stats;
+
+        #[cfg(not(debug_assertions))]
+        MemoStats::default()
+    }
+
+    /// Forgets all cached values and statistics, e.g. between parts.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        #[cfg(debug_assertions)]
+        {
+            self.stats = MemoStats::default();
+        }
+    }
+}
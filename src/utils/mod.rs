@@ -1,6 +1,25 @@
+pub mod bitset;
+pub mod cycle;
+pub mod digit_dp;
+pub mod digits;
 pub mod dynamic_zip;
+pub mod graph;
+pub mod ilp;
+pub mod interner;
+pub mod interval_set;
+pub mod linear_system;
 pub mod location;
 pub mod location3d;
+pub mod memo;
+pub mod mod_int;
+pub mod mst;
+pub mod number_theory;
+pub mod polynomial;
+pub mod priority_queue;
+pub mod range_map;
+pub mod spatial_hash;
+pub mod state_search;
+pub mod sweep;
 pub mod union_find;
 
 use std::str::FromStr;
@@ -0,0 +1,240 @@
+use crate::utils::number_theory::{mod_inv, mod_mul, mod_pow};
+use num::traits::Pow;
+use num::{Num, One, Zero};
+use std::cell::Cell;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+/// Where a [`Modular`] number gets its modulus from.
+pub trait Modulus: Debug + Copy + Default + Eq + Hash {
+    fn modulus() -> u64;
+}
+
+/// Modulus fixed at compile time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Const<const M: u64>;
+
+impl<const M: u64> Modulus for Const<M> {
+    fn modulus() -> u64 {
+        M
+    }
+}
+
+thread_local! {
+    static MODULUS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Modulus chosen at runtime with [`Dynamic::set`], shared by every `DynModInt` on the thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Dynamic;
+
+impl Dynamic {
+    /// Values created under a previous modulus are not reduced again, so don't mix them.
+    pub fn set(modulus: u64) {
+        assert!(modulus > 0, "modulus must be positive");
+        MODULUS.with(|cell| cell.set(modulus));
+    }
+}
+
+impl Modulus for Dynamic {
+    fn modulus() -> u64 {
+        let modulus = MODULUS.with(Cell::get);
+        assert!(modulus > 0, "call Dynamic::set before using DynModInt");
+        modulus
+    }
+}
+
+/// Integer modulo `M::modulus()`, always kept reduced to `0..modulus`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modular<M> {
+    value: u64,
+    _modulus: PhantomData<M>,
+}
+
+pub type ModInt<const M: u64> = Modular<Const<M>>;
+pub type DynModInt = Modular<Dynamic>;
+
+impl<M: Modulus> Modular<M> {
+    pub fn new(value: u64) -> Self {
+        Self {
+            value: value % M::modulus(),
+            _modulus: PhantomData,
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus() -> u64 {
+        M::modulus()
+    }
+
+    /// The multiplicative inverse, if the value is coprime to the modulus.
+    pub fn inverse(&self) -> Option<Self> {
+        mod_inv(self.value, M::modulus()).map(Self::new)
+    }
+}
+
+impl<M: Modulus> From<u64> for Modular<M> {
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<M: Modulus> From<i64> for Modular<M> {
+    fn from(value: i64) -> Self {
+        Self::new((value as i128).rem_euclid(M::modulus() as i128) as u64)
+    }
+}
+
+impl<M> Display for Modular<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<M: Modulus> Add for Modular<M> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let modulus = M::modulus();
+        let (sum, overflow) = self.value.overflowing_add(other.value);
+        let value = if overflow || sum >= modulus {
+            sum.wrapping_sub(modulus)
+        } else {
+            sum
+        };
+
+        Self {
+            value,
+            _modulus: PhantomData,
+        }
+    }
+}
+
+impl<M: Modulus> Neg for Modular<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if self.value == 0 {
+            self
+        } else {
+            Self {
+                value: M::modulus() - self.value,
+                _modulus: PhantomData,
+            }
+        }
+    }
+}
+
+impl<M: Modulus> Sub for Modular<M> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<M: Modulus> Mul for Modular<M> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self {
+            value: mod_mul(self.value, other.value, M::modulus()),
+            _modulus: PhantomData,
+        }
+    }
+}
+
+/// Multiplies by the inverse; panics if `other` is not invertible, see [`Modular::inverse`].
+impl<M: Modulus> Div for Modular<M> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let inverse = other
+            .inverse()
+            .unwrap_or_else(|| panic!("{other} is not invertible modulo {}", M::modulus()));
+        Self::new(mod_mul(self.value, inverse.value, M::modulus()))
+    }
+}
+
+/// Division is exact whenever it is defined, so the remainder is always zero.
+impl<M: Modulus> Rem for Modular<M> {
+    type Output = Self;
+
+    fn rem(self, _other: Self) -> Self {
+        Self::zero()
+    }
+}
+
+impl<M: Modulus> AddAssign for Modular<M> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<M: Modulus> SubAssign for Modular<M> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<M: Modulus> MulAssign for Modular<M> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<M: Modulus> DivAssign for Modular<M> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl<M: Modulus> Zero for Modular<M> {
+    fn zero() -> Self {
+        Self::new(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+
+impl<M: Modulus> One for Modular<M> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<M: Modulus> Num for Modular<M> {
+    type FromStrRadixErr = std::num::ParseIntError;
+
+    fn from_str_radix(input: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        u64::from_str_radix(input, radix).map(Self::new)
+    }
+}
+
+impl<M: Modulus> Pow<u64> for Modular<M> {
+    type Output = Self;
+
+    fn pow(self, exponent: u64) -> Self {
+        Self::new(mod_pow(self.value, exponent, M::modulus()))
+    }
+}
+
+impl<M: Modulus> Sum for Modular<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<M: Modulus> Product for Modular<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
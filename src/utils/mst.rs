@@ -0,0 +1,159 @@
+use crate::utils::union_find::{UnionFind, UnionOutcome};
+use itertools::Itertools;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::hash::Hash;
+
+/// An edge accepted into the spanning forest, together with the number of components left after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connection<W, T> {
+    pub weight: W,
+    pub left: T,
+    pub right: T,
+    pub components: usize,
+}
+
+/// Kruskal's algorithm as an iterator over accepted edges, cheapest first.
+///
+/// Edges are expected as `(weight, left, right)`; rejected edges (closing a cycle) are skipped.
+pub struct Kruskal<T, W, I>
+where
+    T: Eq + Hash + Clone,
+    I: Iterator<Item = (W, T, T)>,
+{
+    union_find: UnionFind<T>,
+    edges: I,
+}
+
+impl<T, W, I> Kruskal<T, W, I>
+where
+    T: Eq + Hash + Clone,
+    I: Iterator<Item = (W, T, T)>,
+{
+    /// Runs over edges that are already in ascending order of weight, consuming them lazily.
+    pub fn from_sorted<E>(nodes: impl IntoIterator<Item = T>, edges: E) -> Self
+    where
+        E: IntoIterator<Item = (W, T, T), IntoIter = I>,
+    {
+        Self {
+            union_find: nodes.into_iter().collect(),
+            edges: edges.into_iter(),
+        }
+    }
+
+    pub fn components(&self) -> usize {
+        self.union_find.component_count()
+    }
+
+    /// Sizes of all current components, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.union_find
+            .groups()
+            .map(|group| group.len())
+            .sorted_unstable_by_key(|&size| Reverse(size))
+            .collect()
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T> {
+        &self.union_find
+    }
+
+    /// Consumes edges until everything is connected and returns the edge that did it.
+    pub fn connecting_edge(&mut self) -> Option<Connection<W, T>> {
+        self.find(|connection| connection.components == 1)
+    }
+}
+
+impl<T, W> Kruskal<T, W, std::vec::IntoIter<(W, T, T)>>
+where
+    T: Eq + Hash + Clone,
+    W: PartialOrd,
+{
+    /// Sorts all edges upfront, for weights that are only `PartialOrd` (e.g. floats).
+    pub fn new(
+        nodes: impl IntoIterator<Item = T>,
+        edges: impl IntoIterator<Item = (W, T, T)>,
+    ) -> Self {
+        let edges = edges
+            .into_iter()
+            .sorted_by(|(a, _, _), (b, _, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        Self::from_sorted(nodes, edges)
+    }
+}
+
+impl<T, W, I> Iterator for Kruskal<T, W, I>
+where
+    T: Eq + Hash + Clone,
+    I: Iterator<Item = (W, T, T)>,
+{
+    type Item = Connection<W, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.union_find.component_count() > 1 {
+            let (weight, left, right) = self.edges.next()?;
+
+            if let Some(UnionOutcome::Merged { .. }) = self.union_find.union(&left, &right) {
+                return Some(Connection {
+                    weight,
+                    left,
+                    right,
+                    components: self.union_find.component_count(),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+struct ByWeight<W, T>(W, T, T);
+
+impl<W: Ord, T> PartialEq for ByWeight<W, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<W: Ord, T> Eq for ByWeight<W, T> {}
+
+impl<W: Ord, T> PartialOrd for ByWeight<W, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: Ord, T> Ord for ByWeight<W, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+/// Edges in ascending order of weight, sorted lazily with a heap.
+pub struct LazySorted<W: Ord, T> {
+    heap: BinaryHeap<ByWeight<W, T>>,
+}
+
+impl<W: Ord, T> Iterator for LazySorted<W, T> {
+    type Item = (W, T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap
+            .pop()
+            .map(|ByWeight(weight, left, right)| (weight, left, right))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+/// Heapifies the edges in O(n), so only the edges actually taken pay for the sorting.
+pub fn sorted_lazily<W: Ord, T>(edges: impl IntoIterator<Item = (W, T, T)>) -> LazySorted<W, T> {
+    LazySorted {
+        heap: edges
+            .into_iter()
+            .map(|(weight, left, right)| ByWeight(weight, left, right))
+            .collect(),
+    }
+}
@@ -0,0 +1,219 @@
+use num::Integer;
+
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+pub fn mod_pow(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    base %= modulus;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r.div_euclid(r);
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1`, if `a` and `modulus` are coprime.
+pub fn mod_inv(a: u64, modulus: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd(a as i128, modulus as i128);
+    (g == 1).then(|| x.rem_euclid(modulus as i128) as u64)
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair, with moduli not necessarily coprime.
+///
+/// Returns `(x, lcm)` with `x` in `0..lcm`, so the solutions are exactly `x + k * lcm`, or `None`
+/// if the congruences contradict each other.
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(x, modulus), (residue, other)| {
+            let (g, p, _) = extended_gcd(modulus, other);
+            let difference = residue - x;
+            if difference % g != 0 {
+                return None;
+            }
+
+            let lcm = modulus / g * other;
+            let step =
+                (difference / g).rem_euclid(other / g) * p.rem_euclid(other / g) % (other / g);
+            Some(((x + modulus * step).rem_euclid(lcm), lcm))
+        })
+}
+
+/// All primes up to and including `limit`, by the sieve of Eratosthenes.
+pub fn sieve(limit: usize) -> Vec<usize> {
+    let mut composite = vec![false; limit + 1];
+    let mut primes = vec![];
+
+    for n in 2..=limit {
+        if composite[n] {
+            continue;
+        }
+        primes.push(n);
+        for multiple in (n * n..=limit).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+
+    primes
+}
+
+/// Deterministic Miller-Rabin for every `u64`.
+pub fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = WITNESSES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+
+    WITNESSES.iter().all(|&witness| {
+        let mut x = mod_pow(witness, odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..shift).any(|_| {
+            x = mod_mul(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+/// Collects `(prime, exponent)` pairs, sorted by prime.
+fn group(mut primes: Vec<u64>) -> Vec<(u64, u32)> {
+    primes.sort_unstable();
+    primes
+        .chunk_by(|a, b| a == b)
+        .map(|chunk| (chunk[0], chunk.len() as u32))
+        .collect()
+}
+
+/// Prime factorisation as `(prime, exponent)` pairs by trial division; fine up to about `10^12`.
+///
+/// Zero and one have no prime factors.
+pub fn factorise_trial(mut n: u64) -> Vec<(u64, u32)> {
+    if n == 0 {
+        return vec![];
+    }
+    let mut primes = vec![];
+
+    let mut divisor = 2;
+    while divisor <= n / divisor {
+        while n.is_multiple_of(divisor) {
+            primes.push(divisor);
+            n /= divisor;
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        primes.push(n);
+    }
+
+    group(primes)
+}
+
+/// Some non-trivial factor of an odd composite `n`.
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c) % n as u128) as u64;
+
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = x.abs_diff(y).gcd(&n);
+        }
+
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!("some increment finds a factor")
+}
+
+/// Prime factorisation as `(prime, exponent)` pairs, using Pollard's rho for large factors.
+///
+/// Zero and one have no prime factors.
+pub fn factorise(mut n: u64) -> Vec<(u64, u32)> {
+    if n == 0 {
+        return vec![];
+    }
+    let mut primes = vec![];
+
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+
+    let mut pending = vec![n];
+    while let Some(n) = pending.pop() {
+        if n == 1 {
+            continue;
+        }
+        if is_prime(n) {
+            primes.push(n);
+            continue;
+        }
+
+        let factor = pollard_rho(n);
+        pending.push(factor);
+        pending.push(n / factor);
+    }
+
+    group(primes)
+}
+
+/// Every divisor of `n`, sorted; empty for zero.
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return vec![];
+    }
+
+    let mut divisors = vec![1];
+    for (prime, exponent) in factorise(n) {
+        let current = divisors.len();
+        let mut power = 1;
+        for _ in 0..exponent {
+            power *= prime;
+            for index in 0..current {
+                divisors.push(divisors[index] * power);
+            }
+        }
+    }
+
+    divisors.sort_unstable();
+    divisors
+}
@@ -0,0 +1,109 @@
+use crate::utils::linear_system::Rational;
+use num::Zero;
+
+/// Rows of repeated differences, starting with `values` itself and ending with the first
+/// all-zero row (or a single value).
+pub fn differences(values: &[i128]) -> Vec<Vec<i128>> {
+    let mut rows = vec![values.to_vec()];
+
+    while let Some(last) = rows.last()
+        && last.len() > 1
+        && last.iter().any(|&value| value != 0)
+    {
+        let next = last.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        rows.push(next);
+    }
+
+    rows
+}
+
+/// Degree of the polynomial the sequence follows: the first `d` whose `d`-th differences are
+/// constant, confirmed by at least two of them. `None` if the sequence is too short to tell.
+pub fn degree(values: &[i128]) -> Option<usize> {
+    let mut row = values.to_vec();
+    for degree in 0.. {
+        if row.len() < 2 {
+            return None;
+        }
+        if row.iter().all(|&value| value == row[0]) {
+            return Some(degree);
+        }
+        row = row.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    }
+    unreachable!()
+}
+
+/// Value at `index` of the unique polynomial of degree below `values.len()` with
+/// `p(i) == values[i]`; `index` may be negative or past the end.
+pub fn newton(values: &[i128], index: i128) -> i128 {
+    let mut row = values.to_vec();
+    let mut binomial = 1;
+    let mut total = 0;
+
+    for j in 0..values.len() as i128 {
+        total += binomial * row[0];
+        // C(index, j + 1) from C(index, j); the division is exact, also for negative indices.
+        binomial = binomial * (index - j) / (j + 1);
+        row = row.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    }
+
+    total
+}
+
+/// Continues the sequence to any `index`, negative to go backwards, once its degree is known.
+pub fn extrapolate(values: &[i128], index: i128) -> Option<i128> {
+    degree(values).map(|degree| newton(&values[..=degree], index))
+}
+
+pub fn next_value(values: &[i128]) -> Option<i128> {
+    extrapolate(values, values.len() as i128)
+}
+
+pub fn previous_value(values: &[i128]) -> Option<i128> {
+    extrapolate(values, -1)
+}
+
+/// Polynomial known from samples at `offset`, `offset + period`, `offset + 2 * period`, ...
+///
+/// Handy when a quantity is only polynomial every `period` steps, e.g. growth across repeated
+/// tiles of a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sampled {
+    pub offset: i128,
+    pub period: i128,
+    pub values: Vec<i128>,
+}
+
+impl Sampled {
+    pub fn new(offset: i128, period: i128, values: Vec<i128>) -> Self {
+        assert!(period > 0, "period must be positive");
+        Self {
+            offset,
+            period,
+            values,
+        }
+    }
+
+    /// Value at `x`, or `None` if `x` is not on the sampling grid.
+    pub fn at(&self, x: i128) -> Option<i128> {
+        let shift = x - self.offset;
+        (shift % self.period == 0).then(|| newton(&self.values, shift / self.period))
+    }
+}
+
+/// Value at `x` of the polynomial through `points`, whose x-coordinates must be distinct.
+pub fn lagrange(points: &[(i128, i128)], x: i128) -> Rational {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, &(xi, yi))| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(Rational::from_integer(yi), |term, (_, &(xj, _))| {
+                    term * Rational::new(x - xj, xi - xj)
+                })
+        })
+        .fold(Rational::zero(), |total, term| total + term)
+}
@@ -0,0 +1,212 @@
+const ABSENT: usize = usize::MAX;
+
+/// Binary min-heap over dense ids (e.g. from an `Interner` or grid cell indices), holding each id
+/// at most once so its priority can be lowered in place instead of pushing duplicates.
+#[derive(Debug, Clone)]
+pub struct IndexedMinHeap<P> {
+    heap: Vec<(P, usize)>,
+    position: Vec<usize>,
+}
+
+impl<P> Default for IndexedMinHeap<P> {
+    fn default() -> Self {
+        Self {
+            heap: vec![],
+            position: vec![],
+        }
+    }
+}
+
+impl<P: Ord> IndexedMinHeap<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Preallocates room for ids `0..ids`; larger ids still work.
+    pub fn with_capacity(ids: usize) -> Self {
+        Self {
+            heap: Vec::with_capacity(ids),
+            position: vec![ABSENT; ids],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.position.get(id).is_some_and(|&index| index != ABSENT)
+    }
+
+    pub fn priority(&self, id: usize) -> Option<&P> {
+        self.contains(id).then(|| &self.heap[self.position[id]].0)
+    }
+
+    pub fn peek_min(&self) -> Option<(usize, &P)> {
+        self.heap.first().map(|(priority, id)| (*id, priority))
+    }
+
+    /// Inserts `id`, or lowers its priority if it is queued with a higher one.
+    ///
+    /// Returns whether anything changed.
+    pub fn push_or_decrease(&mut self, id: usize, priority: P) -> bool {
+        if id >= self.position.len() {
+            self.position.resize(id + 1, ABSENT);
+        }
+
+        let index = match self.position[id] {
+            ABSENT => {
+                self.heap.push((priority, id));
+                self.position[id] = self.heap.len() - 1;
+                self.heap.len() - 1
+            }
+            index if priority < self.heap[index].0 => {
+                self.heap[index].0 = priority;
+                index
+            }
+            _ => return false,
+        };
+
+        self.sift_up(index);
+        true
+    }
+
+    pub fn pop_min(&mut self) -> Option<(usize, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (priority, id) = self.heap.pop()?;
+        self.position[id] = ABSENT;
+        self.sift_down(0);
+
+        Some((id, priority))
+    }
+
+    /// Removes `id` from the queue, returning its priority if it was queued.
+    pub fn remove(&mut self, id: usize) -> Option<P> {
+        if !self.contains(id) {
+            return None;
+        }
+
+        let index = self.position[id];
+        let last = self.heap.len() - 1;
+        self.swap(index, last);
+        let (priority, _) = self.heap.pop()?;
+        self.position[id] = ABSENT;
+
+        if index < self.heap.len() {
+            self.sift_down(index);
+            self.sift_up(index);
+        }
+        Some(priority)
+    }
+
+    pub fn clear(&mut self) {
+        for &(_, id) in &self.heap {
+            self.position[id] = ABSENT;
+        }
+        self.heap.clear();
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.position[self.heap[a].1] = a;
+        self.position[self.heap[b].1] = b;
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.heap[parent].0 <= self.heap[index].0 {
+                break;
+            }
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let smallest = [2 * index + 1, 2 * index + 2]
+                .into_iter()
+                .filter(|&child| child < self.heap.len())
+                .fold(index, |best, child| {
+                    if self.heap[child].0 < self.heap[best].0 {
+                        child
+                    } else {
+                        best
+                    }
+                });
+
+            if smallest == index {
+                break;
+            }
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+}
+
+/// Monotone bucket queue (Dial's algorithm) for small non-negative integer edge weights.
+///
+/// Every pushed priority must lie within `max_step` of the last popped one, which holds for
+/// Dijkstra with weights up to `max_step`. Like `BinaryHeap`, items may be queued more than once.
+#[derive(Debug, Clone)]
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    current: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new(max_step: usize) -> Self {
+        Self {
+            buckets: (0..=max_step).map(|_| vec![]).collect(),
+            current: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, priority: usize, item: T) {
+        let span = self.buckets.len();
+        assert!(
+            (self.current..self.current + span).contains(&priority),
+            "priority {priority} outside of {}..{}",
+            self.current,
+            self.current + span
+        );
+
+        self.buckets[priority % span].push(item);
+        self.len += 1;
+    }
+
+    pub fn pop_min(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let span = self.buckets.len();
+        loop {
+            if let Some(item) = self.buckets[self.current % span].pop() {
+                self.len -= 1;
+                return Some((self.current, item));
+            }
+            self.current += 1;
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::utils::Parsable;
+use crate::utils::interval_set::IntervalSet;
+use nom::character::complete::{line_ending, space1};
+use nom::combinator::map_opt;
+use nom::multi::separated_list1;
+use nom::{IResult, Parser};
+use num::PrimInt;
+use num::traits::{WrappingAdd, WrappingSub};
+use std::ops::RangeInclusive;
+
+/// Moves `value` from a segment starting at `from` to one starting at `to`.
+///
+/// Wrapping arithmetic keeps this exact for signed types, where the offset between segments can
+/// overflow even though the result fits.
+fn shift<T: PrimInt + WrappingAdd + WrappingSub>(value: T, from: T, to: T) -> T {
+    to.wrapping_add(&value.wrapping_sub(&from))
+}
+
+/// Piecewise shift of the whole domain of `T`, as in "source range to destination range" tables.
+///
+/// Stored as `(start, destination)` segments sorted by `start`, the first starting at
+/// `T::min_value()`; each maps `start..next_start` onto `destination..`. Values not covered by
+/// any table row map to themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeMap<T> {
+    segments: Vec<(T, T)>,
+}
+
+impl<T: PrimInt + WrappingAdd + WrappingSub> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: PrimInt + WrappingAdd + WrappingSub> RangeMap<T> {
+    pub fn identity() -> Self {
+        Self {
+            segments: vec![(T::min_value(), T::min_value())],
+        }
+    }
+
+    /// Builds the map from `(destination, source, length)` rows, as written in the puzzles.
+    ///
+    /// Returns `None` if source ranges overlap or a destination range leaves the domain.
+    pub fn from_rows(rows: impl IntoIterator<Item = (T, T, T)>) -> Option<Self> {
+        let mut rows = rows
+            .into_iter()
+            .filter(|&(_, _, length)| length > T::zero())
+            .collect::<Vec<_>>();
+        rows.sort_unstable_by_key(|&(_, source, _)| source);
+
+        let mut segments = vec![];
+        let mut cursor = Some(T::min_value());
+
+        for (destination, source, length) in rows {
+            let start = cursor.filter(|&cursor| cursor <= source)?;
+            destination.checked_add(&(length - T::one()))?;
+
+            if start < source {
+                segments.push((start, start));
+            }
+            segments.push((source, destination));
+            cursor = source.checked_add(&length);
+        }
+
+        if let Some(start) = cursor {
+            segments.push((start, start));
+        }
+
+        Some(Self::normalise(segments))
+    }
+
+    /// Drops segments that merely continue the previous one.
+    fn normalise(segments: Vec<(T, T)>) -> Self {
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(segments.len());
+        for (start, destination) in segments {
+            if let Some(&(last_start, last_destination)) = merged.last()
+                && shift(start, last_start, last_destination) == destination
+            {
+                continue;
+            }
+            merged.push((start, destination));
+        }
+
+        Self { segments: merged }
+    }
+
+    fn end_of(&self, index: usize) -> T {
+        self.segments
+            .get(index + 1)
+            .map_or(T::max_value(), |&(next, _)| next - T::one())
+    }
+
+    /// Source ranges with the destination each one starts at.
+    pub fn iter(&self) -> impl Iterator<Item = (RangeInclusive<T>, T)> + '_ {
+        self.segments
+            .iter()
+            .enumerate()
+            .map(|(index, &(start, destination))| (start..=self.end_of(index), destination))
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let index = self.segments.partition_point(|&(start, _)| start <= value) - 1;
+        let (start, destination) = self.segments[index];
+        shift(value, start, destination)
+    }
+
+    /// Splits `range` along the segments, pairing every source piece with where it maps to.
+    pub fn pieces(
+        &self,
+        range: RangeInclusive<T>,
+    ) -> impl Iterator<Item = (RangeInclusive<T>, RangeInclusive<T>)> + '_ {
+        let (low, high) = range.into_inner();
+        let first = self.segments.partition_point(|&(start, _)| start <= low);
+
+        (first.saturating_sub(1)..self.segments.len())
+            .take_while(move |&index| low <= high && self.segments[index].0 <= high)
+            .map(move |index| {
+                let (start, destination) = self.segments[index];
+                let (from, to) = (start.max(low), self.end_of(index).min(high));
+                (
+                    from..=to,
+                    shift(from, start, destination)..=shift(to, start, destination),
+                )
+            })
+    }
+
+    /// The image of a whole set of values.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.iter()
+            .flat_map(|range| self.pieces(range).map(|(_, mapped)| mapped))
+            .collect()
+    }
+
+    /// The map applying `self` first and `then` afterwards.
+    pub fn compose(&self, then: &Self) -> Self {
+        let segments = self
+            .iter()
+            .flat_map(|(range, destination)| {
+                let start = *range.start();
+                let mapped = destination..=shift(*range.end(), start, destination);
+                then.pieces(mapped)
+                    .map(move |(from, to)| (shift(*from.start(), destination, start), *to.start()))
+            })
+            .collect();
+
+        Self::normalise(segments)
+    }
+
+    /// The inverse map, if the map is a bijection of the domain.
+    pub fn invert(&self) -> Option<Self> {
+        let mut images = self
+            .iter()
+            .map(|(range, destination)| {
+                let end = shift(*range.end(), *range.start(), destination);
+                (destination, end, *range.start())
+            })
+            .collect::<Vec<_>>();
+        images.sort_unstable();
+
+        let mut expected = Some(T::min_value());
+        for &(start, end, _) in &images {
+            if expected != Some(start) {
+                return None;
+            }
+            expected = end.checked_add(&T::one());
+        }
+        if expected.is_some() {
+            return None;
+        }
+
+        Some(Self::normalise(
+            images
+                .into_iter()
+                .map(|(start, _, source)| (start, source))
+                .collect(),
+        ))
+    }
+}
+
+/// Lines of `destination source length`.
+impl<'a, T: PrimInt + WrappingAdd + WrappingSub + Parsable<'a>> Parsable<'a> for RangeMap<T> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        let row = (T::parse, space1, T::parse, space1, T::parse)
+            .map(|(destination, _, source, _, length)| (destination, source, length));
+
+        map_opt(separated_list1(line_ending, row), Self::from_rows).parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap() -> RangeMap<u8> {
+        RangeMap::from_rows([(200, 10, 20), (10, 200, 20)]).unwrap()
+    }
+
+    fn squash() -> RangeMap<u8> {
+        RangeMap::from_rows([(0, 5, 10), (100, 0, 5), (250, 240, 6)]).unwrap()
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let (_, map) = RangeMap::<u64>::parse("50 98 2\n52 50 48").unwrap();
+
+        assert_eq!(
+            [79, 14, 55, 13, 98, 99, 100].map(|seed| map.map(seed)),
+            [81, 14, 57, 13, 50, 51, 100]
+        );
+        assert_eq!(RangeMap::<u8>::from_rows([(0, 5, 3), (10, 6, 2)]), None);
+        assert_eq!(RangeMap::<u8>::from_rows([(250, 0, 10)]), None);
+        assert_eq!(
+            RangeMap::<u8>::from_rows([(7, 7, 10)]),
+            Some(RangeMap::identity())
+        );
+    }
+
+    #[test]
+    fn test_signed_domain() {
+        let map = RangeMap::<i8>::from_rows([(-128, 100, 28)]).unwrap();
+
+        assert_eq!(map.map(100), -128);
+        assert_eq!(map.map(127), -101);
+        assert_eq!(map.map(-128), -128);
+    }
+
+    #[test]
+    fn test_compose() {
+        let (first, then) = (swap(), squash());
+        let composed = first.compose(&then);
+
+        for value in u8::MIN..=u8::MAX {
+            assert_eq!(composed.map(value), then.map(first.map(value)));
+        }
+    }
+
+    #[test]
+    fn test_invert() {
+        let map = swap();
+        let inverse = map.invert().unwrap();
+
+        for value in u8::MIN..=u8::MAX {
+            assert_eq!(inverse.map(map.map(value)), value);
+        }
+        assert_eq!(map.compose(&inverse), RangeMap::identity());
+        assert_eq!(squash().invert(), None);
+    }
+
+    #[test]
+    fn test_map_set() {
+        let map = squash();
+        let set = IntervalSet::from_iter([3..=12, 238..=243]);
+
+        let expected = set
+            .iter()
+            .flatten()
+            .map(|value| {
+                let mapped = map.map(value);
+                mapped..=mapped
+            })
+            .collect::<IntervalSet<u8>>();
+        assert_eq!(map.map_set(&set), expected);
+    }
+}
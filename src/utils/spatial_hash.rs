@@ -0,0 +1,377 @@
+use crate::utils::location::Location;
+use crate::utils::location3d::Location3;
+use num::traits::Euclid;
+use num::{CheckedAdd, CheckedMul, One, PrimInt, Zero, range_inclusive};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    Manhattan,
+    Chebyshev,
+    Euclidean,
+}
+
+impl Metric {
+    /// Checks the distance without leaving integers; euclidean compares squared lengths.
+    pub fn within<P: SpatialPoint>(&self, a: &P, b: &P, radius: P::Coord) -> bool {
+        let mut offsets = a.offsets(b);
+
+        match self {
+            Metric::Manhattan => offsets
+                .try_fold(P::Coord::zero(), |acc, d| acc.checked_add(&d))
+                .is_some_and(|distance| distance <= radius),
+            Metric::Chebyshev => offsets.all(|d| d <= radius),
+            Metric::Euclidean => {
+                let radius = radius.checked_mul(&radius);
+                offsets
+                    .try_fold(P::Coord::zero(), |acc, d| {
+                        acc.checked_add(&d.checked_mul(&d)?)
+                    })
+                    .is_some_and(|distance| radius.is_none_or(|radius| distance <= radius))
+            }
+        }
+    }
+}
+
+/// A point that can be bucketed into the cells of a uniform grid.
+pub trait SpatialPoint: Copy + Eq + Hash {
+    type Coord: PrimInt + Euclid + Hash;
+    type Cell: Copy + Eq + Hash + Ord;
+
+    fn cell(&self, size: Self::Coord) -> Self::Cell;
+
+    /// All cells at most `reach` cells away from `cell` along every axis, including itself.
+    fn cells_around(cell: Self::Cell, reach: Self::Coord) -> impl Iterator<Item = Self::Cell>;
+
+    /// Absolute differences along each axis.
+    fn offsets(&self, other: &Self) -> impl Iterator<Item = Self::Coord>;
+}
+
+fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    if a > b { a - b } else { b - a }
+}
+
+fn around<T: PrimInt>(center: T, reach: T) -> impl Iterator<Item = T> + Clone {
+    range_inclusive(center.saturating_sub(reach), center.saturating_add(reach))
+}
+
+impl<T: PrimInt + Euclid + Hash> SpatialPoint for Location<T> {
+    type Coord = T;
+    type Cell = (T, T);
+
+    fn cell(&self, size: T) -> Self::Cell {
+        (self.x.div_euclid(&size), self.y.div_euclid(&size))
+    }
+
+    fn cells_around((x, y): Self::Cell, reach: T) -> impl Iterator<Item = Self::Cell> {
+        around(x, reach).flat_map(move |x| around(y, reach).map(move |y| (x, y)))
+    }
+
+    fn offsets(&self, other: &Self) -> impl Iterator<Item = T> {
+        [abs_diff(self.x, other.x), abs_diff(self.y, other.y)].into_iter()
+    }
+}
+
+impl<T: PrimInt + Euclid + Hash> SpatialPoint for Location3<T> {
+    type Coord = T;
+    type Cell = (T, T, T);
+
+    fn cell(&self, size: T) -> Self::Cell {
+        (
+            self.x.div_euclid(&size),
+            self.y.div_euclid(&size),
+            self.z.div_euclid(&size),
+        )
+    }
+
+    fn cells_around((x, y, z): Self::Cell, reach: T) -> impl Iterator<Item = Self::Cell> {
+        around(x, reach).flat_map(move |x| {
+            around(y, reach).flat_map(move |y| around(z, reach).map(move |z| (x, y, z)))
+        })
+    }
+
+    fn offsets(&self, other: &Self) -> impl Iterator<Item = T> {
+        [
+            abs_diff(self.x, other.x),
+            abs_diff(self.y, other.y),
+            abs_diff(self.z, other.z),
+        ]
+        .into_iter()
+    }
+}
+
+/// Points bucketed into cubic cells of `cell_size`, for radius queries without a tree index.
+///
+/// Queries only look at cells within reach of the radius, so they are cheapest when the cell size
+/// is close to the typical query radius.
+#[derive(Debug, Clone)]
+pub struct SpatialHash<P: SpatialPoint> {
+    cell_size: P::Coord,
+    buckets: HashMap<P::Cell, Vec<P>>,
+    len: usize,
+}
+
+impl<P: SpatialPoint> SpatialHash<P> {
+    pub fn new(cell_size: P::Coord) -> Self {
+        assert!(cell_size > P::Coord::zero(), "cell size must be positive");
+
+        Self {
+            cell_size,
+            buckets: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, point: P) {
+        self.buckets
+            .entry(point.cell(self.cell_size))
+            .or_default()
+            .push(point);
+        self.len += 1;
+    }
+
+    /// Removes one copy of `point`, returning whether it was present.
+    pub fn remove(&mut self, point: &P) -> bool {
+        let cell = point.cell(self.cell_size);
+        let Some(bucket) = self.buckets.get_mut(&cell) else {
+            return false;
+        };
+        let Some(index) = bucket.iter().position(|p| p == point) else {
+            return false;
+        };
+
+        bucket.swap_remove(index);
+        if bucket.is_empty() {
+            self.buckets.remove(&cell);
+        }
+        self.len -= 1;
+
+        true
+    }
+
+    /// Moves `from` to `to`, returning whether `from` was present.
+    pub fn move_point(&mut self, from: &P, to: P) -> bool {
+        if from.cell(self.cell_size) == to.cell(self.cell_size) {
+            let bucket = self.buckets.get_mut(&to.cell(self.cell_size));
+            if let Some(point) = bucket.and_then(|bucket| bucket.iter_mut().find(|p| *p == from)) {
+                *point = to;
+                return true;
+            }
+            return false;
+        }
+
+        let removed = self.remove(from);
+        if removed {
+            self.insert(to);
+        }
+        removed
+    }
+
+    pub fn contains(&self, point: &P) -> bool {
+        self.buckets
+            .get(&point.cell(self.cell_size))
+            .is_some_and(|bucket| bucket.contains(point))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &P> {
+        self.buckets.values().flatten()
+    }
+
+    fn reach(&self, radius: P::Coord) -> P::Coord {
+        let (quotient, remainder) = (radius / self.cell_size, radius % self.cell_size);
+        if remainder.is_zero() {
+            quotient
+        } else {
+            quotient + P::Coord::one()
+        }
+    }
+
+    /// All points within `radius` of `center`, including `center` itself if it is stored.
+    pub fn within(&self, center: P, radius: P::Coord, metric: Metric) -> impl Iterator<Item = &P> {
+        P::cells_around(center.cell(self.cell_size), self.reach(radius))
+            .filter_map(|cell| self.buckets.get(&cell))
+            .flatten()
+            .filter(move |point| metric.within(&center, point, radius))
+    }
+
+    /// All unordered pairs of stored points that are within `radius` of each other.
+    pub fn pairs_within(&self, radius: P::Coord, metric: Metric) -> Vec<(P, P)> {
+        let reach = self.reach(radius);
+        let mut pairs = vec![];
+
+        for (&cell, bucket) in &self.buckets {
+            for (index, a) in bucket.iter().enumerate() {
+                for b in &bucket[index + 1..] {
+                    if metric.within(a, b, radius) {
+                        pairs.push((*a, *b));
+                    }
+                }
+            }
+
+            // Every pair of distinct cells is seen from both sides, so only look forwards.
+            for other in P::cells_around(cell, reach).filter(|&other| other > cell) {
+                let Some(other_bucket) = self.buckets.get(&other) else {
+                    continue;
+                };
+
+                for a in bucket {
+                    for b in other_bucket {
+                        if metric.within(a, b, radius) {
+                            pairs.push((*a, *b));
+                        }
+                    }
+                }
+            }
+        }
+
+        pairs
+    }
+}
+
+impl<P: SpatialPoint> Extend<P> for SpatialHash<P> {
+    fn extend<I: IntoIterator<Item = P>>(&mut self, iter: I) {
+        for point in iter {
+            self.insert(point);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: [Metric; 3] = [Metric::Manhattan, Metric::Chebyshev, Metric::Euclidean];
+
+    /// Scattered points around the origin, including negative coordinates and one duplicate.
+    fn points() -> Vec<Location<i32>> {
+        let mut seed = 17u32;
+        let mut points = (0..60)
+            .map(|_| {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                let x = (seed >> 16) as i32 % 41 - 20;
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                let y = (seed >> 16) as i32 % 41 - 20;
+                Location::new(x, y)
+            })
+            .collect::<Vec<_>>();
+        points.push(points[0]);
+        points
+    }
+
+    fn key(point: &Location<i32>) -> (i32, i32) {
+        (point.x, point.y)
+    }
+
+    fn sorted_pairs(
+        pairs: impl IntoIterator<Item = (Location<i32>, Location<i32>)>,
+    ) -> Vec<((i32, i32), (i32, i32))> {
+        let mut pairs = pairs
+            .into_iter()
+            .map(|(a, b)| (key(&a).min(key(&b)), key(&a).max(key(&b))))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn test_within() {
+        let points = points();
+
+        for cell_size in [1, 3, 7] {
+            let mut hash = SpatialHash::new(cell_size);
+            hash.extend(points.iter().copied());
+
+            for metric in METRICS {
+                for radius in [0, 2, 5, 9] {
+                    let center = Location::new(-3, 4);
+                    let mut found = hash
+                        .within(center, radius, metric)
+                        .map(key)
+                        .collect::<Vec<_>>();
+                    let mut expected = points
+                        .iter()
+                        .filter(|point| metric.within(&center, *point, radius))
+                        .map(key)
+                        .collect::<Vec<_>>();
+                    found.sort();
+                    expected.sort();
+                    assert_eq!(found, expected, "{metric:?} {radius} {cell_size}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pairs_within() {
+        let points = points();
+
+        for cell_size in [1, 3, 7] {
+            let mut hash = SpatialHash::new(cell_size);
+            hash.extend(points.iter().copied());
+
+            for metric in METRICS {
+                for radius in [0, 2, 5, 9] {
+                    let expected = (0..points.len())
+                        .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+                        .map(|(i, j)| (points[i], points[j]))
+                        .filter(|(a, b)| metric.within(a, b, radius));
+
+                    assert_eq!(
+                        sorted_pairs(hash.pairs_within(radius, metric)),
+                        sorted_pairs(expected),
+                        "{metric:?} {radius} {cell_size}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_updates() {
+        let mut hash = SpatialHash::new(4);
+        hash.extend([
+            Location::new(1, 1),
+            Location::new(1, 1),
+            Location::new(-5, 2),
+        ]);
+        assert_eq!(hash.len(), 3);
+
+        assert!(hash.remove(&Location::new(1, 1)));
+        assert!(hash.contains(&Location::new(1, 1)));
+        assert!(!hash.remove(&Location::new(9, 9)));
+
+        assert!(hash.move_point(&Location::new(1, 1), Location::new(2, 2)));
+        assert!(hash.move_point(&Location::new(-5, 2), Location::new(30, -30)));
+        assert!(!hash.move_point(&Location::new(-5, 2), Location::new(0, 0)));
+        assert!(!hash.contains(&Location::new(1, 1)));
+        assert_eq!(hash.len(), 2);
+        assert_eq!(
+            hash.within(Location::new(30, -31), 1, Metric::Manhattan)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_three_dimensions() {
+        let mut hash = SpatialHash::new(2);
+        hash.extend([
+            Location3::new(0, 0, 0),
+            Location3::new(1, 1, 1),
+            Location3::new(3, 0, 0),
+        ]);
+
+        assert_eq!(hash.pairs_within(1, Metric::Chebyshev).len(), 1);
+        assert_eq!(hash.pairs_within(3, Metric::Manhattan).len(), 2);
+        assert_eq!(hash.pairs_within(2, Metric::Euclidean).len(), 1);
+    }
+}
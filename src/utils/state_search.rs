@@ -0,0 +1,224 @@
+use num::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Counters collected during the last search, printable next to a solution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Distinct states recorded.
+    pub visited: usize,
+    /// States whose transitions were generated.
+    pub expanded: usize,
+    /// States discarded by the pruning bound.
+    pub pruned: usize,
+    /// Largest number of states waiting at once.
+    pub max_frontier: usize,
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} visited, {} expanded, {} pruned, max frontier {}",
+            self.visited, self.expanded, self.pruned, self.max_frontier
+        )
+    }
+}
+
+/// A goal state, the cost of reaching it, and the number of transitions taken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S, C> {
+    pub state: S,
+    pub cost: C,
+    pub steps: usize,
+}
+
+/// Search over user-defined states, given as `transitions` to `(next_state, cost)` pairs.
+///
+/// An optional `prune(state, cost_so_far)` bound discards states that cannot lead anywhere
+/// useful; it must never discard a state on the optimal path.
+pub struct StateSearch<S, C, T, G, P = fn(&S, &C) -> bool> {
+    transitions: T,
+    is_goal: G,
+    prune: P,
+    stats: SearchStats,
+    _marker: PhantomData<fn(S) -> C>,
+}
+
+impl<S, C, T, G> StateSearch<S, C, T, G> {
+    pub fn new(transitions: T, is_goal: G) -> Self {
+        Self {
+            transitions,
+            is_goal,
+            prune: |_, _| false,
+            stats: SearchStats::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S, C, T, G, P, I> StateSearch<S, C, T, G, P>
+where
+    S: Hash + Eq + Clone,
+    C: Zero + Ord + Copy,
+    T: Fn(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: Fn(&S) -> bool,
+    P: Fn(&S, &C) -> bool,
+{
+    pub fn with_prune<Q: Fn(&S, &C) -> bool>(self, prune: Q) -> StateSearch<S, C, T, G, Q> {
+        StateSearch {
+            transitions: self.transitions,
+            is_goal: self.is_goal,
+            prune,
+            stats: self.stats,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    fn keep(&mut self, state: &S, cost: &C) -> bool {
+        let pruned = (self.prune)(state, cost);
+        if pruned {
+            self.stats.pruned += 1;
+        }
+        !pruned
+    }
+
+    /// Fewest transitions to a goal; `cost` is summed along the path that was found.
+    pub fn bfs(&mut self, start: S) -> Option<Found<S, C>> {
+        self.stats = SearchStats::default();
+
+        let mut visited = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(start, C::zero(), 0)]);
+
+        while let Some((state, cost, steps)) = queue.pop_front() {
+            if (self.is_goal)(&state) {
+                self.stats.visited = visited.len();
+                return Some(Found { state, cost, steps });
+            }
+
+            self.stats.expanded += 1;
+            for (next, step_cost) in (self.transitions)(&state) {
+                let next_cost = cost + step_cost;
+                if visited.contains(&next) || !self.keep(&next, &next_cost) {
+                    continue;
+                }
+                visited.insert(next.clone());
+                queue.push_back((next, next_cost, steps + 1));
+            }
+
+            self.stats.max_frontier = self.stats.max_frontier.max(queue.len());
+        }
+
+        self.stats.visited = visited.len();
+        None
+    }
+
+    /// Cheapest path to a goal, for non-negative costs.
+    pub fn dijkstra(&mut self, start: S) -> Option<Found<S, C>> {
+        self.stats = SearchStats::default();
+
+        let mut best = HashMap::from([(start.clone(), C::zero())]);
+        let mut arena = vec![(start, 0)];
+        let mut queue = BinaryHeap::from([Reverse((C::zero(), 0))]);
+
+        while let Some(Reverse((cost, id))) = queue.pop() {
+            let (state, steps) = arena[id].clone();
+            if best.get(&state).is_some_and(|&b| b < cost) {
+                continue;
+            }
+
+            if (self.is_goal)(&state) {
+                self.stats.visited = best.len();
+                return Some(Found { state, cost, steps });
+            }
+
+            self.stats.expanded += 1;
+            for (next, step_cost) in (self.transitions)(&state) {
+                let next_cost = cost + step_cost;
+                if best.get(&next).is_some_and(|&b| b <= next_cost) || !self.keep(&next, &next_cost)
+                {
+                    continue;
+                }
+
+                best.insert(next.clone(), next_cost);
+                queue.push(Reverse((next_cost, arena.len())));
+                arena.push((next, steps + 1));
+            }
+
+            self.stats.max_frontier = self.stats.max_frontier.max(queue.len());
+        }
+
+        self.stats.visited = best.len();
+        None
+    }
+
+    /// Cheapest path to a goal by depth-first branch and bound, for non-negative costs.
+    ///
+    /// Remembers the cheapest cost each state was reached with and skips worse revisits, and
+    /// abandons any path that is already at least as expensive as the best goal found. Uses far
+    /// less memory than `dijkstra` when good solutions are found early.
+    pub fn dfs(&mut self, start: S) -> Option<Found<S, C>> {
+        self.stats = SearchStats::default();
+
+        let mut best = HashMap::from([(start.clone(), C::zero())]);
+        let mut found: Option<Found<S, C>> = None;
+        let mut stack = vec![(start, C::zero(), 0)];
+
+        while let Some((state, cost, steps)) = stack.pop() {
+            if best.get(&state).is_some_and(|&b| b < cost)
+                || found.as_ref().is_some_and(|found| found.cost <= cost)
+            {
+                continue;
+            }
+
+            if (self.is_goal)(&state) {
+                found = Some(Found { state, cost, steps });
+                continue;
+            }
+
+            self.stats.expanded += 1;
+            for (next, step_cost) in (self.transitions)(&state) {
+                let next_cost = cost + step_cost;
+                if best.get(&next).is_some_and(|&b| b <= next_cost) || !self.keep(&next, &next_cost)
+                {
+                    continue;
+                }
+
+                best.insert(next.clone(), next_cost);
+                stack.push((next, next_cost, steps + 1));
+            }
+
+            self.stats.max_frontier = self.stats.max_frontier.max(stack.len());
+        }
+
+        self.stats.visited = best.len();
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bfs_prunes_each_state_once() {
+        let edges = |&state: &u32| match state {
+            0 => vec![(1, 1), (2, 1)],
+            1 => vec![(2, 10), (3, 10)],
+            _ => vec![],
+        };
+        let mut search = StateSearch::new(edges, |_: &u32| false).with_prune(|_, &cost| cost > 5);
+
+        assert_eq!(search.bfs(0), None);
+        assert_eq!(search.stats().visited, 3);
+        assert_eq!(search.stats().pruned, 1);
+    }
+}
@@ -0,0 +1,145 @@
+use num::Zero;
+use std::ops::Sub;
+
+/// Which events go first when an open and a close happen at the same position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TieBreak {
+    /// Touching intervals overlap, as with closed `[start, end]` intervals.
+    OpensFirst,
+    /// Touching intervals don't overlap, as with half-open `[start, end)` intervals.
+    ClosesFirst,
+}
+
+/// Stretch between two consecutive events, with the total weight of the intervals covering it.
+///
+/// Ties produce zero-length segments (`start == end`), so the momentary depth at a shared
+/// position is still visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<P, W> {
+    pub start: P,
+    pub end: P,
+    pub depth: W,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Open,
+    Close,
+}
+
+/// Collects weighted open and close events and sweeps over them in order.
+#[derive(Debug, Clone)]
+pub struct Sweep<P, W> {
+    events: Vec<(P, Kind, W)>,
+    tie_break: TieBreak,
+}
+
+impl<P, W> Sweep<P, W>
+where
+    P: Ord + Copy,
+    W: Ord + Copy + Zero + Sub<Output = W>,
+{
+    pub fn new(tie_break: TieBreak) -> Self {
+        Self {
+            events: vec![],
+            tie_break,
+        }
+    }
+
+    pub fn from_intervals(
+        intervals: impl IntoIterator<Item = (P, P, W)>,
+        tie_break: TieBreak,
+    ) -> Self {
+        let mut sweep = Self::new(tie_break);
+        for (start, end, weight) in intervals {
+            sweep.add(start, end, weight);
+        }
+        sweep
+    }
+
+    pub fn open(&mut self, at: P, weight: W) {
+        self.events.push((at, Kind::Open, weight));
+    }
+
+    pub fn close(&mut self, at: P, weight: W) {
+        self.events.push((at, Kind::Close, weight));
+    }
+
+    /// Opens at `start` and closes at `end`.
+    pub fn add(&mut self, start: P, end: P, weight: W) {
+        self.open(start, weight);
+        self.close(end, weight);
+    }
+
+    /// Segments between consecutive events, in order, covering from the first event to the last.
+    pub fn segments(&self) -> Vec<Segment<P, W>> {
+        let first = match self.tie_break {
+            TieBreak::OpensFirst => Kind::Open,
+            TieBreak::ClosesFirst => Kind::Close,
+        };
+
+        let mut events = self.events.clone();
+        events.sort_by_key(|&(at, kind, _)| (at, kind != first));
+
+        let mut depth = W::zero();
+        let mut segments = Vec::with_capacity(events.len().saturating_sub(1));
+        for (index, &(at, kind, weight)) in events.iter().enumerate() {
+            depth = match kind {
+                Kind::Open => depth + weight,
+                Kind::Close => depth - weight,
+            };
+
+            if let Some(&(next, _, _)) = events.get(index + 1) {
+                segments.push(Segment {
+                    start: at,
+                    end: next,
+                    depth,
+                });
+            }
+        }
+
+        segments
+    }
+
+    /// Largest total weight covering any single position; zero without events.
+    pub fn max_overlap(&self) -> W {
+        self.segments()
+            .into_iter()
+            .map(|segment| segment.depth)
+            .fold(W::zero(), W::max)
+    }
+
+    /// Maximal `(start, end)` regions whose depth is at least `k`.
+    ///
+    /// Like [`Sweep::max_overlap`], this counts zero-length segments: with `OpensFirst`, a position
+    /// where intervals merely touch can be its own `(p, p)` region.
+    pub fn covered_at_least(&self, k: W) -> Vec<(P, P)> {
+        let mut regions: Vec<(P, P)> = vec![];
+
+        for segment in self.segments() {
+            if segment.depth < k {
+                continue;
+            }
+
+            match regions.last_mut() {
+                Some((_, end)) if *end == segment.start => *end = segment.end,
+                _ => regions.push((segment.start, segment.end)),
+            }
+        }
+
+        regions
+    }
+
+    /// Total length covered by at least one interval of positive weight.
+    pub fn union_length(&self) -> P
+    where
+        P: Zero + Sub<Output = P>,
+    {
+        self.segments()
+            .into_iter()
+            .filter(|segment| segment.depth > W::zero())
+            .fold(P::zero(), |total, segment| {
+                total + (segment.end - segment.start)
+            })
+    }
+}
@@ -1,55 +1,331 @@
-use std::collections::HashMap;
+use crate::utils::interner::Interner;
 use std::hash::Hash;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnionOutcome<T> {
+    /// The two sets were joined; `absorbed` is the root that now points to `root`.
+    Merged { root: T, absorbed: T },
+    /// Both elements were already in the set with this root.
+    Connected(T),
+}
+
+impl<T> UnionOutcome<T> {
+    pub fn merged(&self) -> bool {
+        matches!(self, UnionOutcome::Merged { .. })
+    }
+
+    pub fn root(&self) -> &T {
+        match self {
+            UnionOutcome::Merged { root, .. } => root,
+            UnionOutcome::Connected(root) => root,
+        }
+    }
+
+    pub fn map<U, F: Fn(T) -> U>(self, f: F) -> UnionOutcome<U> {
+        match self {
+            UnionOutcome::Merged { root, absorbed } => UnionOutcome::Merged {
+                root: f(root),
+                absorbed: f(absorbed),
+            },
+            UnionOutcome::Connected(root) => UnionOutcome::Connected(f(root)),
+        }
+    }
+}
+
+/// Disjoint sets over the dense ids `0..len`.
+#[derive(Debug, Clone, Default)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            components: len,
+        }
+    }
+
+    /// Adds a new singleton set and returns its id.
+    pub fn push(&mut self) -> usize {
+        let id = self.parents.len();
+        self.parents.push(id);
+        self.sizes.push(1);
+        self.components += 1;
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let root = self.root(x);
+
+        let mut current = x;
+        while current != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+
+        root
+    }
+
+    /// Finds the root without path compression, so it can be used through a shared reference.
+    pub fn root(&self, x: usize) -> usize {
+        let mut current = x;
+        while self.parents[current] != current {
+            current = self.parents[current];
+        }
+        current
+    }
+
+    pub fn union(&mut self, x: usize, y: usize) -> UnionOutcome<usize> {
+        let x_root = self.find(x);
+        let y_root = self.find(y);
+
+        if x_root == y_root {
+            return UnionOutcome::Connected(x_root);
+        }
+
+        let (root, absorbed) = if self.sizes[x_root] >= self.sizes[y_root] {
+            (x_root, y_root)
+        } else {
+            (y_root, x_root)
+        };
+
+        self.parents[absorbed] = root;
+        self.sizes[root] += self.sizes[absorbed];
+        self.components -= 1;
+
+        UnionOutcome::Merged { root, absorbed }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    pub fn component_size(&self, x: usize) -> usize {
+        self.sizes[self.root(x)]
+    }
+
+    /// Iterates over all components, each yielded as the list of its members.
+    pub fn groups(&self) -> impl Iterator<Item = Vec<usize>> {
+        let mut groups = vec![vec![]; self.len()];
+
+        for x in 0..self.len() {
+            groups[self.root(x)].push(x);
+        }
+
+        groups.into_iter().filter(|group| !group.is_empty())
+    }
+}
+
+/// Marks a point in the history of a [`RollbackDisjointSet`] that can be returned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Snapshot(usize);
+
+/// Disjoint sets over dense ids whose unions can be undone in reverse order.
+///
+/// Uses union by size without path compression, so `find` is O(log n) and every union is a
+/// single parent change that can be reverted.
+#[derive(Debug, Clone, Default)]
+pub struct RollbackDisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    components: usize,
+    history: Vec<usize>,
+}
+
+impl RollbackDisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            components: len,
+            history: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    pub fn find(&self, x: usize) -> usize {
+        let mut current = x;
+        while self.parents[current] != current {
+            current = self.parents[current];
+        }
+        current
+    }
+
+    pub fn union(&mut self, x: usize, y: usize) -> UnionOutcome<usize> {
+        let x_root = self.find(x);
+        let y_root = self.find(y);
+
+        if x_root == y_root {
+            return UnionOutcome::Connected(x_root);
+        }
+
+        let (root, absorbed) = if self.sizes[x_root] >= self.sizes[y_root] {
+            (x_root, y_root)
+        } else {
+            (y_root, x_root)
+        };
+
+        self.parents[absorbed] = root;
+        self.sizes[root] += self.sizes[absorbed];
+        self.components -= 1;
+        self.history.push(absorbed);
+
+        UnionOutcome::Merged { root, absorbed }
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(self.history.len())
+    }
+
+    /// Undoes every union made since `snapshot` was taken, in O(number of undone unions).
+    pub fn rollback(&mut self, snapshot: Snapshot) {
+        debug_assert!(
+            snapshot.0 <= self.history.len(),
+            "snapshot is from a rolled back future"
+        );
+
+        while self.history.len() > snapshot.0
+            && let Some(absorbed) = self.history.pop()
+        {
+            let root = self.parents[absorbed];
+
+            self.sizes[root] -= self.sizes[absorbed];
+            self.parents[absorbed] = absorbed;
+            self.components += 1;
+        }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    pub fn component_size(&self, x: usize) -> usize {
+        self.sizes[self.find(x)]
+    }
+
+    /// Iterates over all components, each yielded as the list of its members.
+    pub fn groups(&self) -> impl Iterator<Item = Vec<usize>> {
+        let mut groups = vec![vec![]; self.len()];
+
+        for x in 0..self.len() {
+            groups[self.find(x)].push(x);
+        }
+
+        groups.into_iter().filter(|group| !group.is_empty())
+    }
+}
+
+/// Disjoint sets over arbitrary hashable values, interned onto a [`DisjointSet`].
 pub struct UnionFind<T: Eq + Hash + Clone> {
-    data: HashMap<T, T>,
+    interner: Interner<T>,
+    sets: DisjointSet,
+}
+
+impl<T: Eq + Hash + Clone> Default for UnionFind<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: Eq + Hash + Clone> UnionFind<T> {
     pub fn new() -> Self {
         Self {
-            data: HashMap::new(),
+            interner: Interner::new(),
+            sets: DisjointSet::default(),
         }
     }
 
+    /// Adds `x` as a singleton set, unless it is already present.
     pub fn insert(&mut self, x: &T) {
-        self.data.insert(x.clone(), x.clone());
+        if !self.contains(x) {
+            self.interner.intern(x.clone());
+            self.sets.push();
+        }
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.interner.get(x).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.interner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interner.is_empty()
+    }
+
+    pub fn interner(&self) -> &Interner<T> {
+        &self.interner
+    }
+
+    pub fn sets(&self) -> &DisjointSet {
+        &self.sets
+    }
+
+    pub fn find_id(&mut self, x: &T) -> Option<usize> {
+        let id = self.interner.get(x)?;
+        Some(self.sets.find(id))
     }
 
     pub fn find(&mut self, x: &T) -> Option<T> {
-        let group = self.data.get(x).cloned();
+        let root = self.find_id(x)?;
+        Some(self.interner[root].clone())
+    }
 
-        let parent = match group {
-            None => return None,
-            Some(parent) if &parent == x => return Some(parent),
-            Some(parent) => self.find(&parent),
-        };
+    pub fn union(&mut self, x: &T, y: &T) -> Option<UnionOutcome<T>> {
+        let x = self.interner.get(x)?;
+        let y = self.interner.get(y)?;
 
-        if let Some(parent) = parent {
-            self.data.insert(x.clone(), parent.clone());
-            Some(parent)
-        } else {
-            parent
-        }
+        let outcome = self.sets.union(x, y);
+        Some(outcome.map(|id| self.interner[id].clone()))
     }
 
-    pub fn union(&mut self, x: &T, y: &T) -> Option<(T, T)> {
-        let x_parent = self.find(x);
-        let y_parent = self.find(y);
+    pub fn component_count(&self) -> usize {
+        self.sets.component_count()
+    }
 
-        match (x_parent, y_parent) {
-            (Some(x_parent), Some(y_parent)) => {
-                self.data.insert(x_parent.clone(), y_parent.clone());
-                Some((x_parent, y_parent))
-            }
-            _ => None,
-        }
+    pub fn component_size(&self, x: &T) -> Option<usize> {
+        let id = self.interner.get(x)?;
+        Some(self.sets.component_size(id))
+    }
+
+    /// Iterates over all components, each yielded as the list of its members.
+    pub fn groups(&self) -> impl Iterator<Item = Vec<T>> {
+        self.sets.groups().map(|group| {
+            group
+                .into_iter()
+                .map(|id| self.interner[id].clone())
+                .collect()
+        })
     }
 }
 
 impl<T: Eq + Hash + Clone> FromIterator<T> for UnionFind<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let data = iter.into_iter().map(|x| (x.clone(), x)).collect();
-        UnionFind { data }
+        let interner: Interner<T> = iter.into_iter().collect();
+
+        Self {
+            sets: DisjointSet::new(interner.len()),
+            interner,
+        }
     }
 }